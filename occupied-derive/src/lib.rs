@@ -0,0 +1,445 @@
+/*!
+Derive and attribute macros for the [`occupied`](https://docs.rs/occupied)
+crate. You shouldn't depend on this crate directly; instead, enable the
+`derive` feature of `occupied`, which re-exports these macros.
+*/
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt as _;
+use syn::{
+    Data, DeriveInput, Field, Fields, GenericArgument, Index, PathArguments, Type,
+    parse_macro_input,
+};
+
+/**
+Derive `occupied::OverlayOptions` for a struct. Each field is overlaid with
+its own `OverlayOptions` implementation, so `Option` fields are only filled
+in if they're vacant, and nested structs that also derive `OverlayOptions`
+are overlaid recursively. Fields marked with `#[overlay(skip)]` are left
+untouched, and the corresponding field in the lower-priority layer is
+discarded.
+
+# Example
+
+```
+use occupied::OverlayOptions;
+
+#[derive(OverlayOptions, Debug, PartialEq)]
+struct Config {
+    name: Option<String>,
+    timeout: Option<u32>,
+
+    #[overlay(skip)]
+    source: &'static str,
+}
+
+let cli = Config {
+    name: None,
+    timeout: Some(30),
+    source: "cli",
+};
+
+let file = Config {
+    name: Some("server".to_owned()),
+    timeout: Some(10),
+    source: "file",
+};
+
+assert_eq!(
+    cli.overlay(file),
+    Config {
+        name: Some("server".to_owned()),
+        timeout: Some(30),
+        source: "cli",
+    }
+);
+```
+*/
+#[proc_macro_derive(OverlayOptions, attributes(overlay))]
+pub fn derive_overlay_options(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    derive_overlay_options_impl(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn derive_overlay_options_impl(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        Data::Enum(ref data) => {
+            return Err(syn::Error::new(
+                data.enum_token.span,
+                "OverlayOptions can only be derived for structs",
+            ));
+        }
+        Data::Union(ref data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                "OverlayOptions can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut overlays = Vec::new();
+
+    for (index, field) in fields.iter().enumerate() {
+        if is_skipped(field)? {
+            continue;
+        }
+
+        let member = match field.ident {
+            Some(ref ident) => quote! { #ident },
+            None => {
+                let index = Index::from(index);
+                quote! { #index }
+            }
+        };
+
+        overlays.push(quote! {
+            ::occupied::OverlayOptions::fill_vacant_from(
+                &mut self.#member,
+                other.#member,
+            );
+        });
+    }
+
+    // Make sure that `other` is considered used even if every field is
+    // skipped
+    let other = match overlays.is_empty() {
+        true => format_ident!("_other"),
+        false => format_ident!("other"),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::occupied::OverlayOptions for #name #ty_generics #where_clause {
+            fn fill_vacant_from(&mut self, #other: Self) {
+                #(#overlays)*
+            }
+        }
+    })
+}
+
+/// Check a field for an `#[overlay(skip)]` attribute
+fn is_skipped(field: &Field) -> syn::Result<bool> {
+    let mut skip = false;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("overlay"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unrecognized overlay attribute; expected `skip`"))
+            }
+        })?;
+    }
+
+    Ok(skip)
+}
+
+/**
+Generate per-field entry methods for each `Option` field of a struct. For a
+field `foo: Option<T>`, this generates:
+
+- `fn foo_entry(&mut self) -> occupied::Entry<'_, T>`
+- `fn foo_peek(&mut self) -> Option<occupied::Occupied<'_, T>>`
+- `fn foo_emplace(&mut self, value: T) -> occupied::Occupied<'_, T>`
+
+The methods have the same visibility as the field. Fields are recognized as
+options by their type being spelled `Option<T>` (or a path ending in
+`Option<T>`, like `core::option::Option<T>`); other fields are ignored.
+
+# Example
+
+```
+#[occupied::accessors]
+#[derive(Default)]
+struct Config {
+    timeout: Option<u32>,
+    name: Option<String>,
+    retries: u32,
+}
+
+let mut config = Config::default();
+
+assert_eq!(*config.timeout_entry().or_insert(30).get(), 30);
+assert_eq!(*config.timeout_entry().or_insert(60).get(), 30);
+assert!(config.name_peek().is_none());
+
+config.name_emplace("server".to_owned());
+assert_eq!(config.name.as_deref(), Some("server"));
+```
+*/
+#[proc_macro_attribute]
+pub fn accessors(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = TokenStream2::from(attr);
+    let item = parse_macro_input!(item as DeriveInput);
+
+    accessors_impl(attr, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn accessors_impl(attr: TokenStream2, item: DeriveInput) -> syn::Result<TokenStream2> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "accessors doesn't take any arguments",
+        ));
+    }
+
+    let fields = match item.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    data.struct_token.span,
+                    "accessors can only be used on structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &item.ident,
+                "accessors can only be used on structs",
+            ));
+        }
+    };
+
+    let methods = fields.iter().filter_map(|field| {
+        let ident = field.ident.as_ref()?;
+        let inner = option_inner_type(&field.ty)?;
+        let vis = &field.vis;
+
+        let entry = format_ident!("{}_entry", ident);
+        let peek = format_ident!("{}_peek", ident);
+        let emplace = format_ident!("{}_emplace", ident);
+
+        Some(quote! {
+            #[doc = concat!("Get an [`Entry`](::occupied::Entry) for the `", stringify!(#ident), "` field.")]
+            #[inline]
+            #[must_use]
+            #vis fn #entry(&mut self) -> ::occupied::Entry<'_, #inner> {
+                ::occupied::examine(&mut self.#ident)
+            }
+
+            #[doc = concat!("Try to get an [`Occupied`](::occupied::Occupied) reference to the `", stringify!(#ident), "` field.")]
+            #[inline]
+            #[must_use]
+            #vis fn #peek(&mut self) -> ::core::option::Option<::occupied::Occupied<'_, #inner>> {
+                ::occupied::OptionExt::peek_some(&mut self.#ident)
+            }
+
+            #[doc = concat!("Insert a value into the `", stringify!(#ident), "` field, and return an [`Occupied`](::occupied::Occupied) reference to it.")]
+            #[inline]
+            #vis fn #emplace(&mut self, value: #inner) -> ::occupied::Occupied<'_, #inner> {
+                ::occupied::OptionExt::emplace(&mut self.#ident, value)
+            }
+        })
+    });
+
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#methods)*
+        }
+    })
+}
+
+/**
+Derive an enum naming each `Option` field of a struct, so that a field can
+be chosen at runtime (for instance, from user input) and manipulated through
+a type-erased [`DynSlot`](https://docs.rs/occupied/latest/occupied/trait.DynSlot.html).
+For a struct `Foo`, this generates:
+
+- `enum FooSlot`, with a variant for each `Option` field, named in
+  `UpperCamelCase`, along with `FooSlot::ALL`, `FooSlot::name`, and
+  `FooSlot::from_name` to convert to and from the field names.
+- `fn slot(&self, slot: FooSlot) -> &dyn occupied::DynSlot`
+- `fn slot_mut(&mut self, slot: FooSlot) -> &mut dyn occupied::DynSlot`
+
+The enum and methods have the same visibility as the struct. Fields are
+recognized as options in the same way as for [`accessors`]; other fields are
+ignored. The option payloads must be `'static`, as required by `DynSlot`.
+
+# Example
+
+```
+use occupied::SlotEnum;
+
+#[derive(SlotEnum, Default)]
+struct Config {
+    timeout: Option<u32>,
+    server_name: Option<String>,
+    retries: u32,
+}
+
+let mut config = Config::default();
+let field = ConfigSlot::from_name("server_name").unwrap();
+assert_eq!(field, ConfigSlot::ServerName);
+
+config
+    .slot_mut(field)
+    .entry::<String>()
+    .unwrap()
+    .or_insert_with(|| "server".to_owned());
+
+assert_eq!(config.server_name.as_deref(), Some("server"));
+assert!(!config.slot(ConfigSlot::Timeout).is_occupied());
+assert_eq!(ConfigSlot::ALL, [ConfigSlot::Timeout, ConfigSlot::ServerName]);
+```
+*/
+#[proc_macro_derive(SlotEnum)]
+pub fn derive_slot_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    derive_slot_enum_impl(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn derive_slot_enum_impl(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    data.struct_token.span,
+                    "SlotEnum can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "SlotEnum can only be derived for structs",
+            ));
+        }
+    };
+
+    let fields: Vec<_> = fields
+        .iter()
+        .filter(|field| option_inner_type(&field.ty).is_some())
+        .filter_map(|field| field.ident.as_ref())
+        .collect();
+
+    let variants: Vec<_> = fields
+        .iter()
+        .map(|ident| format_ident!("{}", upper_camel_case(&ident.unraw().to_string())))
+        .collect();
+
+    let names: Vec<_> = fields
+        .iter()
+        .map(|ident| ident.unraw().to_string())
+        .collect();
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let slot_enum = format_ident!("{}Slot", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        #[doc = concat!("The `Option` fields of [`", stringify!(#name), "`].")]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #slot_enum {
+            #(
+                #[doc = concat!("The `", #names, "` field.")]
+                #variants,
+            )*
+        }
+
+        impl #slot_enum {
+            /// Every field, in declaration order.
+            #vis const ALL: &'static [Self] = &[#(Self::#variants),*];
+
+            /// Get the name of this field.
+            #[inline]
+            #[must_use]
+            #vis const fn name(self) -> &'static str {
+                match self {
+                    #(Self::#variants => #names,)*
+                }
+            }
+
+            /// Look up a field by name.
+            #[inline]
+            #[must_use]
+            #vis fn from_name(name: &str) -> ::core::option::Option<Self> {
+                match name {
+                    #(#names => ::core::option::Option::Some(Self::#variants),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Get a type-erased reference to the field chosen by `slot`.
+            #[inline]
+            #[must_use]
+            #vis fn slot(&self, slot: #slot_enum) -> &dyn ::occupied::DynSlot {
+                match slot {
+                    #(#slot_enum::#variants => &self.#fields,)*
+                }
+            }
+
+            /// Get a type-erased mutable reference to the field chosen by
+            /// `slot`.
+            #[inline]
+            #[must_use]
+            #vis fn slot_mut(&mut self, slot: #slot_enum) -> &mut dyn ::occupied::DynSlot {
+                match slot {
+                    #(#slot_enum::#variants => &mut self.#fields,)*
+                }
+            }
+        }
+    })
+}
+
+/// Convert a `snake_case` field name to `UpperCamelCase`
+fn upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first.into_iter().chain(chars)
+        })
+        .collect()
+}
+
+/// If `ty` is spelled as `Option<T>`, get the `T`
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else { return None };
+
+    if path.qself.is_some() {
+        return None;
+    }
+
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(ref args) = segment.arguments else {
+        return None;
+    };
+
+    match args.args.first() {
+        Some(GenericArgument::Type(inner)) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
@@ -0,0 +1,392 @@
+/*!
+Lock-free optional integers, which pack the occupancy and the value into a
+single atomic word.
+*/
+
+use core::fmt;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::AtomicU64;
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::Ordering;
+
+/// Generate an atomic optional integer type. `$encode` and `$decode` convert
+/// between the optional value and the raw atomic word.
+#[cfg(target_has_atomic = "64")]
+macro_rules! atomic_option {
+    (
+        $(#[$meta:meta])*
+        $Name:ident($Atomic:ident: $Raw:ty) for $Int:ty;
+        fn encode($value:ident) $encode:block
+        fn decode($raw:ident) $decode:block
+    ) => {
+        $(#[$meta])*
+        pub struct $Name {
+            raw: $Atomic,
+        }
+
+        impl $Name {
+            #[inline(always)]
+            const fn encode($value: Option<$Int>) -> $Raw $encode
+
+            #[inline(always)]
+            const fn decode($raw: $Raw) -> Option<$Int> $decode
+
+            /// Create a new atomic optional integer, containing `value`.
+            #[inline]
+            #[must_use]
+            pub const fn new(value: Option<$Int>) -> Self {
+                Self {
+                    raw: $Atomic::new(Self::encode(value)),
+                }
+            }
+
+            /// Load the current value.
+            #[inline]
+            #[must_use]
+            pub fn load(&self, order: Ordering) -> Option<$Int> {
+                Self::decode(self.raw.load(order))
+            }
+
+            /// Store a new value, replacing the current one.
+            #[inline]
+            pub fn store(&self, value: Option<$Int>, order: Ordering) {
+                self.raw.store(Self::encode(value), order);
+            }
+
+            /// Store a new value, returning the previous one.
+            #[inline]
+            pub fn swap(&self, value: Option<$Int>, order: Ordering) -> Option<$Int> {
+                Self::decode(self.raw.swap(Self::encode(value), order))
+            }
+
+            /// Remove the current value, leaving this vacant, and return it.
+            #[inline]
+            pub fn take(&self, order: Ordering) -> Option<$Int> {
+                self.swap(None, order)
+            }
+
+            /// Insert `value`, but only if this is currently vacant. Returns
+            /// the current value if it's occupied.
+            #[inline]
+            pub fn insert_if_vacant(
+                &self,
+                value: $Int,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<(), $Int> {
+                match self.raw.compare_exchange(
+                    Self::encode(None),
+                    Self::encode(Some(value)),
+                    success,
+                    failure,
+                ) {
+                    Ok(_) => Ok(()),
+                    // Safety: the exchange only fails if the current value
+                    // isn't vacant
+                    Err(current) => Err(unwrap_unchecked!(Self::decode(current))),
+                }
+            }
+
+            /// Fetch the current value, and apply `f` to it to get a new
+            /// value, retrying if the value was changed concurrently. If `f`
+            /// returns [`None`], the value is left unchanged. Returns the
+            /// previous value as `Ok` if it was updated, or `Err` otherwise.
+            #[inline]
+            pub fn fetch_update(
+                &self,
+                set_order: Ordering,
+                fetch_order: Ordering,
+                mut f: impl FnMut(Option<$Int>) -> Option<Option<$Int>>,
+            ) -> Result<Option<$Int>, Option<$Int>> {
+                self.raw
+                    .fetch_update(set_order, fetch_order, |raw| {
+                        f(Self::decode(raw)).map(Self::encode)
+                    })
+                    .map(Self::decode)
+                    .map_err(Self::decode)
+            }
+
+            /// Consume this atomic, returning the value.
+            #[inline]
+            #[must_use]
+            pub const fn into_inner(self) -> Option<$Int> {
+                Self::decode(self.raw.into_inner())
+            }
+        }
+
+        impl Default for $Name {
+            /// Create a new, vacant atomic optional integer.
+            #[inline]
+            fn default() -> Self {
+                Self::new(None)
+            }
+        }
+
+        impl From<Option<$Int>> for $Name {
+            #[inline]
+            fn from(value: Option<$Int>) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl fmt::Debug for $Name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+            }
+        }
+    };
+}
+
+#[cfg(target_has_atomic = "64")]
+atomic_option! {
+    /**
+    An optional [`u32`] which can be safely shared between threads. The
+    occupancy and the value are packed together into an [`AtomicU64`], so
+    every `u32` can be stored, with no sentinel value reserved for vacancy.
+
+    # Example
+
+    ```
+    use std::sync::atomic::Ordering;
+    use occupied::AtomicOptionU32;
+
+    let slot = AtomicOptionU32::default();
+
+    assert_eq!(slot.insert_if_vacant(5, Ordering::AcqRel, Ordering::Acquire), Ok(()));
+    assert_eq!(slot.insert_if_vacant(6, Ordering::AcqRel, Ordering::Acquire), Err(5));
+
+    let previous = slot.fetch_update(Ordering::AcqRel, Ordering::Acquire, |value| {
+        Some(value.map(|value| value + 1))
+    });
+    assert_eq!(previous, Ok(Some(5)));
+
+    assert_eq!(slot.take(Ordering::AcqRel), Some(6));
+    assert_eq!(slot.load(Ordering::Acquire), None);
+    ```
+
+    The whole range of `u32` is available, including the boundary values:
+
+    ```
+    use std::sync::atomic::Ordering;
+    use occupied::AtomicOptionU32;
+
+    let slot = AtomicOptionU32::new(Some(u32::MAX));
+    assert_eq!(slot.load(Ordering::Acquire), Some(u32::MAX));
+
+    assert_eq!(slot.swap(Some(0), Ordering::AcqRel), Some(u32::MAX));
+    assert_eq!(slot.take(Ordering::AcqRel), Some(0));
+    assert_eq!(slot.into_inner(), None);
+    ```
+    */
+    AtomicOptionU32(AtomicU64: u64) for u32;
+
+    fn encode(value) {
+        match value {
+            Some(value) => (1 << 32) | value as u64,
+            None => 0,
+        }
+    }
+
+    fn decode(raw) {
+        match raw >> 32 {
+            0 => None,
+            _ => Some(raw as u32),
+        }
+    }
+}
+
+/**
+An optional [`usize`] which can be safely shared between threads, such as an
+optional index. It's stored in an [`AtomicUsize`], with the top bit marking
+occupancy, so values up to [`AtomicOptionUsize::MAX`] (`usize::MAX >> 1`) can
+be stored. That covers every index into a slice, since no allocation can be
+larger than [`isize::MAX`] bytes.
+
+Operations which store a value are fallible, and return the value back as an
+`Err` if it's larger than [`MAX`][Self::MAX], rather than panicking.
+
+# Example
+
+```
+use std::sync::atomic::Ordering;
+use occupied::AtomicOptionUsize;
+
+let slot = AtomicOptionUsize::try_new(Some(3)).unwrap();
+
+assert_eq!(slot.try_swap(None, Ordering::AcqRel), Ok(Some(3)));
+assert_eq!(slot.try_insert_if_vacant(4, Ordering::AcqRel, Ordering::Acquire), Ok(Ok(())));
+assert_eq!(slot.try_insert_if_vacant(5, Ordering::AcqRel, Ordering::Acquire), Ok(Err(4)));
+assert_eq!(slot.into_inner(), Some(4));
+```
+
+Values are stored intact up to the boundary, and rejected past it:
+
+```
+use std::sync::atomic::Ordering;
+use occupied::AtomicOptionUsize;
+
+let slot = AtomicOptionUsize::try_new(Some(AtomicOptionUsize::MAX)).unwrap();
+assert_eq!(slot.load(Ordering::Acquire), Some(AtomicOptionUsize::MAX));
+
+assert_eq!(slot.try_store(Some(usize::MAX), Ordering::Release), Err(usize::MAX));
+assert_eq!(slot.load(Ordering::Acquire), Some(AtomicOptionUsize::MAX));
+
+assert!(AtomicOptionUsize::try_new(Some(usize::MAX)).is_err());
+```
+*/
+#[cfg(target_has_atomic = "ptr")]
+pub struct AtomicOptionUsize {
+    raw: AtomicUsize,
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl AtomicOptionUsize {
+    /// The largest value that can be stored.
+    pub const MAX: usize = usize::MAX >> 1;
+
+    const OCCUPIED: usize = !Self::MAX;
+
+    /// Encode an optional value as a raw word, or return the value back if
+    /// it's too large to be stored.
+    #[inline(always)]
+    const fn encode(value: Option<usize>) -> Result<usize, usize> {
+        match value {
+            Some(value) if value > Self::MAX => Err(value),
+            Some(value) => Ok(value | Self::OCCUPIED),
+            None => Ok(0),
+        }
+    }
+
+    #[inline(always)]
+    const fn decode(raw: usize) -> Option<usize> {
+        match raw & Self::OCCUPIED {
+            0 => None,
+            _ => Some(raw & Self::MAX),
+        }
+    }
+
+    /// Create a new, vacant atomic optional [`usize`].
+    #[inline]
+    #[must_use]
+    pub const fn vacant() -> Self {
+        Self {
+            raw: AtomicUsize::new(0),
+        }
+    }
+
+    /// Create a new atomic optional [`usize`], containing `value`. Returns
+    /// the value back if it's larger than [`MAX`][Self::MAX].
+    #[inline]
+    pub const fn try_new(value: Option<usize>) -> Result<Self, usize> {
+        match Self::encode(value) {
+            Ok(raw) => Ok(Self {
+                raw: AtomicUsize::new(raw),
+            }),
+            Err(value) => Err(value),
+        }
+    }
+
+    /// Load the current value.
+    #[inline]
+    #[must_use]
+    pub fn load(&self, order: Ordering) -> Option<usize> {
+        Self::decode(self.raw.load(order))
+    }
+
+    /// Store a new value, replacing the current one. Returns the value back
+    /// (leaving the current one unchanged) if it's larger than
+    /// [`MAX`][Self::MAX].
+    #[inline]
+    pub fn try_store(&self, value: Option<usize>, order: Ordering) -> Result<(), usize> {
+        self.raw.store(Self::encode(value)?, order);
+        Ok(())
+    }
+
+    /// Store a new value, returning the previous one. Returns the new value
+    /// back (leaving the current one unchanged) if it's larger than
+    /// [`MAX`][Self::MAX].
+    #[inline]
+    pub fn try_swap(&self, value: Option<usize>, order: Ordering) -> Result<Option<usize>, usize> {
+        Ok(Self::decode(self.raw.swap(Self::encode(value)?, order)))
+    }
+
+    /// Remove the current value, leaving this vacant, and return it.
+    #[inline]
+    pub fn take(&self, order: Ordering) -> Option<usize> {
+        Self::decode(self.raw.swap(0, order))
+    }
+
+    /// Insert `value`, but only if this is currently vacant. The inner
+    /// result holds the current value if it's occupied; the outer `Err`
+    /// returns `value` back if it's larger than [`MAX`][Self::MAX].
+    #[inline]
+    pub fn try_insert_if_vacant(
+        &self,
+        value: usize,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Result<(), usize>, usize> {
+        let raw = Self::encode(Some(value))?;
+
+        Ok(match self.raw.compare_exchange(0, raw, success, failure) {
+            Ok(_) => Ok(()),
+            // Safety: the exchange only fails if the current value isn't
+            // vacant
+            Err(current) => Err(unwrap_unchecked!(Self::decode(current))),
+        })
+    }
+
+    /// Fetch the current value, and apply `f` to it to get a new value,
+    /// retrying if the value was changed concurrently. If `f` returns
+    /// [`None`], or a value larger than [`MAX`][Self::MAX], the value is left
+    /// unchanged. Returns the previous value as `Ok` if it was updated, or
+    /// `Err` otherwise.
+    #[inline]
+    pub fn fetch_update(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: impl FnMut(Option<usize>) -> Option<Option<usize>>,
+    ) -> Result<Option<usize>, Option<usize>> {
+        self.raw
+            .fetch_update(set_order, fetch_order, |raw| {
+                f(Self::decode(raw)).and_then(|value| Self::encode(value).ok())
+            })
+            .map(Self::decode)
+            .map_err(Self::decode)
+    }
+
+    /// Consume this atomic, returning the value.
+    #[inline]
+    #[must_use]
+    pub const fn into_inner(self) -> Option<usize> {
+        Self::decode(self.raw.into_inner())
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl Default for AtomicOptionUsize {
+    /// Create a new, vacant atomic optional [`usize`].
+    #[inline]
+    fn default() -> Self {
+        Self::vacant()
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl TryFrom<Option<usize>> for AtomicOptionUsize {
+    type Error = usize;
+
+    #[inline]
+    fn try_from(value: Option<usize>) -> Result<Self, usize> {
+        Self::try_new(value)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl fmt::Debug for AtomicOptionUsize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+    }
+}
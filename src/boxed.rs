@@ -0,0 +1,51 @@
+/*!
+Unboxing helpers for options of boxes.
+*/
+
+use alloc::boxed::Box;
+
+use crate::Occupied;
+
+impl<'a, T: ?Sized> Occupied<'a, Box<T>> {
+    /// Get a reference to the boxed item, rather than to the [`Box`].
+    #[inline]
+    #[must_use]
+    pub fn get_deref(&self) -> &T {
+        self.get()
+    }
+
+    /// Get a mutable reference to the boxed item, rather than to the
+    /// [`Box`].
+    #[inline]
+    #[must_use]
+    pub fn get_deref_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<'a, T> Occupied<'a, Box<T>> {
+    /**
+    Take the [`Box`] out of the option, leaving [`None`] in its place, and
+    unbox the item in it.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut slot = Some(Box::new([1, 2, 3]));
+
+    let mut occupied = slot.peek_some().unwrap();
+    occupied.get_deref_mut()[0] = 10;
+    assert_eq!(occupied.get_deref(), &[10, 2, 3]);
+
+    let array: [i32; 3] = occupied.take_unbox();
+    assert_eq!(array, [10, 2, 3]);
+    assert_eq!(slot, None);
+    ```
+    */
+    #[inline]
+    pub fn take_unbox(self) -> T {
+        *self.take()
+    }
+}
@@ -0,0 +1,195 @@
+/*!
+Branded slices of options, which can issue storable index tokens proving that
+a particular slot is occupied.
+*/
+
+use core::{fmt, marker::PhantomData};
+
+/// An invariant lifetime, used to tie an [`OccupiedIndex`] to the specific
+/// [`BrandedSlots`] that created it.
+type Brand<'brand> = PhantomData<fn(&'brand ()) -> &'brand ()>;
+
+/**
+A slice of options that has been branded with a unique `'brand` lifetime by
+[`brand_slots`]. It can issue [`OccupiedIndex`] tokens, which can later be
+redeemed for the items in the slots without re-checking them.
+
+To keep these tokens valid, a [`BrandedSlots`] only ever allows vacant slots
+to be filled, never occupied slots to be vacated. This means that it never
+hands out an [`Occupied`][crate::Occupied], since that could be used to
+[`.take()`][crate::Occupied::take] the item.
+*/
+pub struct BrandedSlots<'brand, 'a, T> {
+    slots: &'a mut [Option<T>],
+    brand: Brand<'brand>,
+}
+
+/**
+A token proving that a particular slot in a [`BrandedSlots`] is occupied. It
+is [`Copy`], so it can be freely stored in work lists and redeemed later with
+[`BrandedSlots::get`] or [`BrandedSlots::get_mut`]. The `'brand` lifetime
+ensures that it can only be redeemed with the [`BrandedSlots`] that created
+it.
+*/
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OccupiedIndex<'brand> {
+    index: usize,
+    brand: Brand<'brand>,
+}
+
+impl OccupiedIndex<'_> {
+    /// Get the index of the occupied slot that this token refers to.
+    #[inline(always)]
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Debug for OccupiedIndex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OccupiedIndex").field(&self.index).finish()
+    }
+}
+
+/**
+Brand a slice of options, and pass the resulting [`BrandedSlots`] to `body`.
+The brand is unique to this call, so [`OccupiedIndex`] tokens created inside
+of `body` can't be used with any other slice.
+
+# Example
+
+```
+use occupied::brand_slots;
+
+let mut slots = [Some(1), None, Some(3), None, Some(5)];
+
+let total = brand_slots(&mut slots, |mut slots| {
+    // Precompute a work list of the occupied slots
+    let work: Vec<_> = slots.occupied_indices().collect();
+
+    // Fill in a gap, which doesn't invalidate the work list
+    let new = slots.insert(1, 10).unwrap();
+
+    for &index in &work {
+        *slots.get_mut(index) *= 2;
+    }
+
+    work.iter().map(|&index| slots.get(index)).sum::<i32>() + slots.get(new)
+});
+
+assert_eq!(total, 28);
+assert_eq!(slots, [Some(2), Some(10), Some(6), None, Some(10)]);
+```
+*/
+#[inline]
+pub fn brand_slots<T, R>(
+    slots: &mut [Option<T>],
+    body: impl for<'brand> FnOnce(BrandedSlots<'brand, '_, T>) -> R,
+) -> R {
+    body(BrandedSlots {
+        slots,
+        brand: PhantomData,
+    })
+}
+
+impl<'brand, T> BrandedSlots<'brand, '_, T> {
+    /// Get the number of slots.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns true if there are no slots.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Get a view of all of the slots.
+    #[inline]
+    #[must_use]
+    pub const fn as_slice(&self) -> &[Option<T>] {
+        self.slots
+    }
+
+    /// Check if the slot at `index` is occupied, and return an
+    /// [`OccupiedIndex`] for it if so. Returns [`None`] if the slot is vacant
+    /// or out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn check(&self, index: usize) -> Option<OccupiedIndex<'brand>> {
+        match self.slots.get(index) {
+            Some(Some(_)) => Some(OccupiedIndex {
+                index,
+                brand: PhantomData,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Iterate over [`OccupiedIndex`] tokens for all of the occupied slots,
+    /// in order.
+    #[inline]
+    pub fn occupied_indices(&self) -> impl Iterator<Item = OccupiedIndex<'brand>> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.is_some())
+            .map(|(index, _)| OccupiedIndex {
+                index,
+                brand: PhantomData,
+            })
+    }
+
+    /**
+    Insert an item into the vacant slot at `index`, and return an
+    [`OccupiedIndex`] for it. Returns the item back if the slot is occupied
+    or out of bounds.
+    */
+    #[inline]
+    pub fn insert(&mut self, index: usize, item: T) -> Result<OccupiedIndex<'brand>, T> {
+        match self.slots.get_mut(index) {
+            Some(slot @ None) => {
+                *slot = Some(item);
+                Ok(OccupiedIndex {
+                    index,
+                    brand: PhantomData,
+                })
+            }
+            _ => Err(item),
+        }
+    }
+
+    /// Get a reference to the item in an occupied slot, without checking
+    /// that it's occupied.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: OccupiedIndex<'brand>) -> &T {
+        // Safety: an `OccupiedIndex` is only created for an in-bounds,
+        // occupied slot of the slice with the same brand, and slots in a
+        // `BrandedSlots` are never vacated.
+        let slot = unwrap_unchecked!(self.slots.get(index.index));
+        unwrap_unchecked!(slot.as_ref())
+    }
+
+    /// Get a mutable reference to the item in an occupied slot, without
+    /// checking that it's occupied.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self, index: OccupiedIndex<'brand>) -> &mut T {
+        // Safety: an `OccupiedIndex` is only created for an in-bounds,
+        // occupied slot of the slice with the same brand, and slots in a
+        // `BrandedSlots` are never vacated.
+        let slot = unwrap_unchecked!(self.slots.get_mut(index.index));
+        unwrap_unchecked!(slot.as_mut())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BrandedSlots<'_, '_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BrandedSlots").field(&self.slots).finish()
+    }
+}
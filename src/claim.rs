@@ -0,0 +1,209 @@
+/*!
+All-or-nothing claims over several options at once, which produce proofs for
+all of them only if they're all occupied.
+*/
+
+use crate::Occupied;
+
+/**
+Get [`Occupied`] references to both `a` and `b`, but only if they're both
+occupied. Otherwise, return [`None`], leaving both options untouched.
+
+# Example
+
+```
+let mut name = Some("ferris");
+let mut age = None;
+
+assert!(occupied::try_zip(&mut name, &mut age).is_none());
+
+age = Some(8);
+let (name, age) = occupied::try_zip(&mut name, &mut age).unwrap();
+assert_eq!((name.take(), age.take()), ("ferris", 8));
+```
+*/
+#[inline]
+#[must_use]
+pub const fn try_zip<'a, A, B>(
+    a: &'a mut Option<A>,
+    b: &'a mut Option<B>,
+) -> Option<(Occupied<'a, A>, Occupied<'a, B>)> {
+    match (Occupied::new(a), Occupied::new(b)) {
+        (Some(a), Some(b)) => Some((a, b)),
+        _ => None,
+    }
+}
+
+/// Get [`Occupied`] references to all of `a`, `b`, and `c`, but only if
+/// they're all occupied. Otherwise, return [`None`], leaving all the options
+/// untouched.
+#[inline]
+#[must_use]
+pub const fn try_zip3<'a, A, B, C>(
+    a: &'a mut Option<A>,
+    b: &'a mut Option<B>,
+    c: &'a mut Option<C>,
+) -> Option<(Occupied<'a, A>, Occupied<'a, B>, Occupied<'a, C>)> {
+    match (Occupied::new(a), Occupied::new(b), Occupied::new(c)) {
+        (Some(a), Some(b), Some(c)) => Some((a, b, c)),
+        _ => None,
+    }
+}
+
+/**
+Get [`Occupied`] references to every option in an array, but only if
+they're all occupied. Otherwise, return [`None`], leaving all the options
+untouched.
+
+# Example
+
+```
+fn try_unwrap_all<T, const N: usize>(options: &mut [Option<T>; N]) -> Option<[T; N]> {
+    Some(occupied::peek_all(options)?.map(|occupied| occupied.take()))
+}
+
+let mut opts = [Some(1), Some(2), Some(3), None];
+
+assert_eq!(try_unwrap_all(&mut opts), None);
+assert_eq!(opts, [Some(1), Some(2), Some(3), None]);
+
+opts[3] = Some(4);
+
+assert_eq!(try_unwrap_all(&mut opts), Some([1, 2, 3, 4]));
+assert_eq!(opts, [None, None, None, None]);
+```
+*/
+#[inline]
+#[must_use]
+pub fn peek_all<T, const N: usize>(options: &mut [Option<T>; N]) -> Option<[Occupied<'_, T>; N]> {
+    if options.iter().any(Option::is_none) {
+        return None;
+    }
+
+    // Safety: we just checked that every option is `Some`
+    Some(
+        options
+            .each_mut()
+            .map(|option| new_unchecked!(Occupied, option)),
+    )
+}
+
+/**
+All-or-nothing claims over a tuple of mutable references to options, which
+may hold different types. This is implemented for tuples of up to 12
+options.
+
+# Example
+
+```
+use occupied::TuplePeek as _;
+
+let mut name = Some(String::from("ferris"));
+let mut age = Some(8);
+let mut alive = None;
+
+assert!((&mut name, &mut age, &mut alive).peek_all().is_none());
+
+alive = Some(true);
+let (name, age, alive) = (&mut name, &mut age, &mut alive).take_all().unwrap();
+assert_eq!((name.as_str(), age, alive), ("ferris", 8, true));
+```
+*/
+pub trait TuplePeek<'a> {
+    /// A tuple of [`Occupied`] references to each of the options.
+    type Occupied;
+
+    /// A tuple of the items in each of the options.
+    type Items;
+
+    /// Get [`Occupied`] references to all of the options, but only if
+    /// they're all occupied. Otherwise, return [`None`], leaving all the
+    /// options untouched.
+    #[must_use]
+    fn peek_all(self) -> Option<Self::Occupied>;
+
+    /// Take the items out of all of the options, but only if they're all
+    /// occupied. Otherwise, return [`None`], leaving all the options
+    /// untouched.
+    fn take_all(self) -> Option<Self::Items>;
+}
+
+/// Implement [`TuplePeek`] for a tuple of references to options of the given
+/// types.
+macro_rules! tuple_peek {
+    ($($T:ident $option:ident),+) => {
+        impl<'a, $($T),+> TuplePeek<'a> for ($(&'a mut Option<$T>,)+) {
+            type Occupied = ($(Occupied<'a, $T>,)+);
+            type Items = ($($T,)+);
+
+            #[inline]
+            fn peek_all(self) -> Option<Self::Occupied> {
+                let ($($option,)+) = self;
+                Some(($(Occupied::new($option)?,)+))
+            }
+
+            #[inline]
+            fn take_all(self) -> Option<Self::Items> {
+                let ($($option,)+) = self.peek_all()?;
+                Some(($($option.take(),)+))
+            }
+        }
+    };
+}
+
+tuple_peek!(A a);
+tuple_peek!(A a, B b);
+tuple_peek!(A a, B b, C c);
+tuple_peek!(A a, B b, C c, D d);
+tuple_peek!(A a, B b, C c, D d, E e);
+tuple_peek!(A a, B b, C c, D d, E e, F f);
+tuple_peek!(A a, B b, C c, D d, E e, F f, G g);
+tuple_peek!(A a, B b, C c, D d, E e, F f, G g, H h);
+tuple_peek!(A a, B b, C c, D d, E e, F f, G g, H h, I i);
+tuple_peek!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j);
+tuple_peek!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j, K k);
+tuple_peek!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j, K k, L l);
+
+/**
+Get [`Occupied`] references to any number of options, but only if they're
+all occupied. Each argument is an expression evaluating to a
+`&mut Option<_>`, and the options may hold different types. Evaluates to a
+tuple of [`Occupied`] references, or [`None`] (leaving all the options
+untouched) if any of the options are vacant. Arguments after the first
+vacant option aren't evaluated.
+
+# Example
+
+```
+let mut host = Some("localhost");
+let mut port = Some(8080);
+let mut user = Some(String::from("ferris"));
+let mut password = None;
+let mut database = Some("app");
+
+assert!(occupied::claim!(&mut host, &mut port, &mut user, &mut password, &mut database).is_none());
+
+password = Some(String::from("hunter2"));
+let (host, port, user, password, database) =
+    occupied::claim!(&mut host, &mut port, &mut user, &mut password, &mut database).unwrap();
+
+assert_eq!(host.take(), "localhost");
+assert_eq!(*port.get(), 8080);
+assert_eq!(user.get(), "ferris");
+assert_eq!(password.take(), "hunter2");
+assert_eq!(*database.get(), "app");
+```
+*/
+#[macro_export]
+macro_rules! claim {
+    ($($option:expr),+ $(,)?) => {
+        'claim: {
+            ::core::option::Option::Some(($(
+                match $crate::Occupied::new($option) {
+                    ::core::option::Option::Some(occupied) => occupied,
+                    ::core::option::Option::None => break 'claim ::core::option::Option::None,
+                },
+            )+))
+        }
+    };
+}
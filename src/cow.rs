@@ -0,0 +1,339 @@
+/*!
+Proofs over [`Cow`], guaranteeing which variant it currently holds.
+*/
+
+use core::fmt::{self, Debug};
+
+use alloc::borrow::{Cow, ToOwned};
+
+/// As with [`Occupied`][crate::Occupied] and [`Vacant`][crate::Vacant], the
+/// `cow` field is only directly accessible in here, to force the use of
+/// `unsafe{}` to construct the proofs.
+mod internals {
+    use alloc::borrow::{Cow, ToOwned};
+
+    /**
+    A reference to a [`Cow`] that is statically guaranteed to be
+    [`Cow::Owned`], meaning that mutable access to the contents never needs to
+    clone anything.
+    */
+    pub struct CowOwned<'a, 'b, B: ?Sized + ToOwned> {
+        cow: &'a mut Cow<'b, B>,
+    }
+
+    impl<'a, 'b, B: ?Sized + ToOwned> CowOwned<'a, 'b, B> {
+        /**
+        Create a new [`CowOwned`], referencing a [`Cow`] that is definitely
+        [`Cow::Owned`].
+
+        # Safety
+
+        The `cow` parameter MUST be [`Cow::Owned`].
+        */
+        #[inline(always)]
+        #[must_use]
+        #[allow(unsafe_code)]
+        pub const unsafe fn new_unchecked(cow: &'a mut Cow<'b, B>) -> Self {
+            Self { cow }
+        }
+
+        /// Safe equivalent of [`new_unchecked`][Self::new_unchecked], used
+        /// internally when `forbid-unsafe` is enabled.
+        #[cfg(feature = "forbid-unsafe")]
+        #[inline(always)]
+        #[must_use]
+        pub(crate) const fn new_trusted(cow: &'a mut Cow<'b, B>) -> Self {
+            Self { cow }
+        }
+
+        /**
+        Get a mutable reference to the underlying [`Cow`]. This destroys
+        `self`, because we lose the guarantee that the cow is owned.
+        */
+        #[inline(always)]
+        #[must_use]
+        pub const fn into_inner(self) -> &'a mut Cow<'b, B> {
+            self.cow
+        }
+
+        /// Get a shared reference to the underlying [`Cow`].
+        #[inline(always)]
+        #[must_use]
+        pub const fn as_cow(&self) -> &Cow<'b, B> {
+            self.cow
+        }
+
+        /// Get a mutable reference to the underlying [`Cow`], without
+        /// destroying `self`. Only used internally, since callers could use
+        /// it to break the invariant.
+        #[inline(always)]
+        pub(crate) const fn as_cow_mut(&mut self) -> &mut Cow<'b, B> {
+            self.cow
+        }
+    }
+
+    /**
+    A reference to a [`Cow`] that is statically guaranteed to be
+    [`Cow::Borrowed`], meaning that the borrowed data can be retrieved with
+    its full original lifetime.
+    */
+    pub struct CowBorrowed<'a, 'b, B: ?Sized + ToOwned> {
+        cow: &'a mut Cow<'b, B>,
+    }
+
+    impl<'a, 'b, B: ?Sized + ToOwned> CowBorrowed<'a, 'b, B> {
+        /**
+        Create a new [`CowBorrowed`], referencing a [`Cow`] that is definitely
+        [`Cow::Borrowed`].
+
+        # Safety
+
+        The `cow` parameter MUST be [`Cow::Borrowed`].
+        */
+        #[inline(always)]
+        #[must_use]
+        #[allow(unsafe_code)]
+        pub const unsafe fn new_unchecked(cow: &'a mut Cow<'b, B>) -> Self {
+            Self { cow }
+        }
+
+        /// Safe equivalent of [`new_unchecked`][Self::new_unchecked], used
+        /// internally when `forbid-unsafe` is enabled.
+        #[cfg(feature = "forbid-unsafe")]
+        #[inline(always)]
+        #[must_use]
+        pub(crate) const fn new_trusted(cow: &'a mut Cow<'b, B>) -> Self {
+            Self { cow }
+        }
+
+        /**
+        Get a mutable reference to the underlying [`Cow`]. This destroys
+        `self`, because we lose the guarantee that the cow is borrowed.
+        */
+        #[inline(always)]
+        #[must_use]
+        pub const fn into_inner(self) -> &'a mut Cow<'b, B> {
+            self.cow
+        }
+
+        /// Get a shared reference to the underlying [`Cow`].
+        #[inline(always)]
+        #[must_use]
+        pub const fn as_cow(&self) -> &Cow<'b, B> {
+            self.cow
+        }
+    }
+}
+
+pub use internals::{CowBorrowed, CowOwned};
+
+impl<'a, 'b, B: ?Sized + ToOwned> CowOwned<'a, 'b, B> {
+    /**
+    Try to create a new [`CowOwned`] instance, referencing a [`Cow`] that is
+    definitely [`Cow::Owned`]. Returns [`None`] if the cow is borrowed.
+    */
+    #[inline]
+    #[must_use]
+    pub fn new(cow: &'a mut Cow<'b, B>) -> Option<Self> {
+        match cow {
+            Cow::Owned(_) => Some(new_unchecked!(CowOwned, cow)),
+            Cow::Borrowed(_) => None,
+        }
+    }
+
+    /// Get an immutable reference to the owned data in the referenced cow.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> &B::Owned {
+        match self.as_cow() {
+            Cow::Owned(owned) => owned,
+            // Safety: the cow in a `CowOwned` is guaranteed to be owned
+            Cow::Borrowed(_) => unreachable_unchecked!(),
+        }
+    }
+
+    /**
+    Get a mutable reference to the owned data in the referenced cow. Unlike
+    [`Cow::to_mut`], this never clones anything.
+
+    # Example
+
+    ```
+    use std::borrow::Cow;
+    use occupied::CowExt as _;
+
+    let mut cow: Cow<'_, str> = Cow::Owned(String::from("hello"));
+    let mut owned = cow.peek_owned().unwrap();
+
+    owned.get_mut().push_str(", world");
+
+    assert_eq!(cow, "hello, world");
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut B::Owned {
+        match self.as_cow_mut() {
+            Cow::Owned(owned) => owned,
+            // Safety: the cow in a `CowOwned` is guaranteed to be owned
+            Cow::Borrowed(_) => unreachable_unchecked!(),
+        }
+    }
+
+    /**
+    Get a mutable reference to the owned data with the original lifetime.
+    */
+    #[inline]
+    #[must_use]
+    pub fn into_mut(self) -> &'a mut B::Owned {
+        match self.into_inner() {
+            Cow::Owned(owned) => owned,
+            // Safety: the cow in a `CowOwned` is guaranteed to be owned
+            Cow::Borrowed(_) => unreachable_unchecked!(),
+        }
+    }
+}
+
+impl<'a, 'b, B: ?Sized + ToOwned> CowBorrowed<'a, 'b, B> {
+    /**
+    Try to create a new [`CowBorrowed`] instance, referencing a [`Cow`] that
+    is definitely [`Cow::Borrowed`]. Returns [`None`] if the cow is owned.
+    */
+    #[inline]
+    #[must_use]
+    pub fn new(cow: &'a mut Cow<'b, B>) -> Option<Self> {
+        match cow {
+            Cow::Borrowed(_) => Some(new_unchecked!(CowBorrowed, cow)),
+            Cow::Owned(_) => None,
+        }
+    }
+
+    /**
+    Get the borrowed data, with its full original lifetime. This is a cheap
+    copy of a reference.
+
+    # Example
+
+    ```
+    use std::borrow::Cow;
+    use occupied::CowExt as _;
+
+    let data = String::from("hello");
+    let mut cow: Cow<'_, str> = Cow::Borrowed(&data);
+
+    let borrowed = cow.peek_borrowed().unwrap();
+    let reference: &str = borrowed.get();
+
+    assert_eq!(reference, "hello");
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> &'b B {
+        match *self.as_cow() {
+            Cow::Borrowed(borrowed) => borrowed,
+            // Safety: the cow in a `CowBorrowed` is guaranteed to be borrowed
+            Cow::Owned(_) => unreachable_unchecked!(),
+        }
+    }
+
+    /**
+    Clone the borrowed data into the cow, and return a [`CowOwned`] proof
+    for the now-owned cow. This is the only place where a clone happens.
+
+    # Example
+
+    ```
+    use std::borrow::Cow;
+    use occupied::CowExt as _;
+
+    let data = [1, 2, 3];
+    let mut cow: Cow<'_, [i32]> = Cow::Borrowed(&data);
+
+    let borrowed = cow.peek_borrowed().unwrap();
+    let mut owned = borrowed.into_owned();
+    owned.get_mut().push(4);
+
+    assert_eq!(cow, [1, 2, 3, 4].as_slice());
+    assert_eq!(data, [1, 2, 3]);
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub fn into_owned(self) -> CowOwned<'a, 'b, B> {
+        let borrowed = self.get();
+        let cow = self.into_inner();
+        *cow = Cow::Owned(borrowed.to_owned());
+
+        // Safety: we just made the cow owned
+        new_unchecked!(CowOwned, cow)
+    }
+}
+
+impl<'b, B: ?Sized + ToOwned> Debug for CowOwned<'_, 'b, B>
+where
+    Cow<'b, B>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CowOwned")
+            .field("cow", self.as_cow())
+            .finish()
+    }
+}
+
+impl<'b, B: ?Sized + ToOwned> Debug for CowBorrowed<'_, 'b, B>
+where
+    Cow<'b, B>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CowBorrowed")
+            .field("cow", self.as_cow())
+            .finish()
+    }
+}
+
+impl<B: ?Sized + ToOwned> AsRef<B> for CowOwned<'_, '_, B> {
+    fn as_ref(&self) -> &B {
+        self.as_cow()
+    }
+}
+
+impl<B: ?Sized + ToOwned> AsRef<B> for CowBorrowed<'_, '_, B> {
+    fn as_ref(&self) -> &B {
+        self.get()
+    }
+}
+
+/**
+Additional methods for [`Cow`], granting access to [`CowOwned`] and
+[`CowBorrowed`] references to it.
+*/
+pub trait CowExt<'b, B: ?Sized + ToOwned> {
+    /**
+    Try to get a [`CowOwned`] reference to this cow. Returns [`None`] if
+    `self` is [`Cow::Borrowed`]; otherwise returns a [`CowOwned`] which can be
+    used to mutate the owned data without any hidden clones.
+    */
+    #[must_use]
+    fn peek_owned(&mut self) -> Option<CowOwned<'_, 'b, B>>;
+
+    /**
+    Try to get a [`CowBorrowed`] reference to this cow. Returns [`None`] if
+    `self` is [`Cow::Owned`]; otherwise returns a [`CowBorrowed`] which can be
+    used to cheaply access the borrowed data.
+    */
+    #[must_use]
+    fn peek_borrowed(&mut self) -> Option<CowBorrowed<'_, 'b, B>>;
+}
+
+impl<'b, B: ?Sized + ToOwned> CowExt<'b, B> for Cow<'b, B> {
+    #[inline(always)]
+    fn peek_owned(&mut self) -> Option<CowOwned<'_, 'b, B>> {
+        CowOwned::new(self)
+    }
+
+    #[inline(always)]
+    fn peek_borrowed(&mut self) -> Option<CowBorrowed<'_, 'b, B>> {
+        CowBorrowed::new(self)
+    }
+}
@@ -0,0 +1,79 @@
+/*!
+Constant-time occupancy queries and payload selection, using [`subtle`].
+*/
+
+use subtle::{Choice, ConditionallySelectable};
+
+use crate::Occupied;
+
+/**
+Constant-time occupancy queries for [`Option`], for options whose occupancy
+depends on secret data.
+
+These return a [`Choice`] rather than a `bool`, so that the occupancy can
+be combined with other secret conditions without branching.
+*/
+pub trait CtOptionExt {
+    /// Returns a [`Choice`] that is 1 if this option is [`Some`], and 0
+    /// otherwise.
+    #[must_use]
+    fn ct_is_some(&self) -> Choice;
+
+    /// Returns a [`Choice`] that is 1 if this option is [`None`], and 0
+    /// otherwise.
+    #[inline]
+    #[must_use]
+    fn ct_is_none(&self) -> Choice {
+        !self.ct_is_some()
+    }
+}
+
+impl<T> CtOptionExt for Option<T> {
+    #[inline]
+    fn ct_is_some(&self) -> Choice {
+        Choice::from(u8::from(self.is_some()))
+    }
+}
+
+impl<T: ConditionallySelectable> Occupied<'_, T> {
+    /**
+    Select between the items in two occupied options in constant time,
+    returning a copy of `a`'s item if `choice` is 0, or `b`'s item if `choice`
+    is 1. Because both options are known to be occupied, this never needs to
+    branch on either of their discriminants.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+    use subtle::Choice;
+
+    let mut a = Some(1u32);
+    let mut b = Some(2u32);
+    let a = a.peek_some().unwrap();
+    let b = b.peek_some().unwrap();
+
+    assert_eq!(occupied::Occupied::ct_select(&a, &b, Choice::from(0)), 1);
+    assert_eq!(occupied::Occupied::ct_select(&a, &b, Choice::from(1)), 2);
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub fn ct_select(a: &Self, b: &Self, choice: Choice) -> T {
+        T::conditional_select(a.get(), b.get(), choice)
+    }
+
+    /// Replace the item in this option with a copy of `other` if `choice`
+    /// is 1, or leave it unchanged if `choice` is 0, in constant time.
+    #[inline]
+    pub fn ct_assign(&mut self, other: &T, choice: Choice) {
+        self.get_mut().conditional_assign(other, choice);
+    }
+
+    /// Swap the items in two occupied options if `choice` is 1, or leave
+    /// them unchanged if `choice` is 0, in constant time.
+    #[inline]
+    pub fn ct_swap(a: &mut Self, b: &mut Self, choice: Choice) {
+        T::conditional_swap(a.get_mut(), b.get_mut(), choice);
+    }
+}
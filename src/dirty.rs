@@ -0,0 +1,171 @@
+/*!
+A slot that tracks whether it's been modified since it was last read.
+*/
+
+use crate::{DynSlot, Entry, Occupied, OptionExt as _, examine};
+
+/**
+An optional value with a dirty flag, which is set whenever the slot is
+changed: when a value is inserted or removed, and whenever the value is
+mutably accessed. The flag is cleared by reading the value with
+[`get_clean`][Self::get_clean] or [`take_if_dirty`][Self::take_if_dirty],
+so that render caches and sync layers can get the value only if it changed.
+
+# Example
+
+```
+use occupied::DirtySlot;
+
+let mut slot = DirtySlot::new();
+assert!(!slot.is_dirty());
+
+slot.insert(String::from("hello"));
+assert_eq!(slot.get_clean().map(String::as_str), Some("hello"));
+
+// The flag was cleared by the read
+assert!(slot.get_clean().is_none());
+
+slot.get_mut().unwrap().push_str(", world");
+assert_eq!(slot.take_if_dirty().as_deref(), Some("hello, world"));
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct DirtySlot<T> {
+    slot: Option<T>,
+    dirty: bool,
+}
+
+impl<T> DirtySlot<T> {
+    /// Create a new, vacant, clean [`DirtySlot`].
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            slot: None,
+            dirty: false,
+        }
+    }
+
+    /// Create a new [`DirtySlot`] containing `value`. The slot starts out
+    /// dirty, since `value` hasn't been read yet.
+    #[inline]
+    #[must_use]
+    pub const fn with_value(value: T) -> Self {
+        Self {
+            slot: Some(value),
+            dirty: true,
+        }
+    }
+
+    /// Returns true if the slot has been changed since it was last read with
+    /// [`get_clean`][Self::get_clean] or [`take_if_dirty`][Self::take_if_dirty].
+    #[inline]
+    #[must_use]
+    pub const fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag without reading the value.
+    #[inline]
+    pub const fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Get a reference to the value in this slot, if any. This doesn't
+    /// affect the dirty flag.
+    #[inline]
+    #[must_use]
+    pub const fn get(&self) -> Option<&T> {
+        self.slot.as_ref()
+    }
+
+    /// Get a reference to the value in this slot, but only if the slot is
+    /// dirty and occupied. This clears the dirty flag, even if the slot is
+    /// vacant (because the value was removed).
+    #[inline]
+    #[must_use]
+    pub const fn get_clean(&mut self) -> Option<&T> {
+        match core::mem::replace(&mut self.dirty, false) {
+            true => self.slot.as_ref(),
+            false => None,
+        }
+    }
+
+    /// Remove the value from this slot, but only if the slot is dirty and
+    /// occupied. This clears the dirty flag, even if the slot is vacant.
+    #[inline]
+    #[must_use]
+    pub const fn take_if_dirty(&mut self) -> Option<T> {
+        match core::mem::replace(&mut self.dirty, false) {
+            true => self.slot.take(),
+            false => None,
+        }
+    }
+
+    /// Get a mutable reference to the value in this slot, if any. This sets
+    /// the dirty flag if the slot is occupied.
+    #[inline]
+    #[must_use]
+    pub const fn get_mut(&mut self) -> Option<&mut T> {
+        if self.slot.is_some() {
+            self.dirty = true;
+        }
+
+        self.slot.as_mut()
+    }
+
+    /// Insert a value into this slot, replacing any existing value, and
+    /// return an [`Occupied`] reference to it. This sets the dirty flag.
+    #[inline]
+    pub fn insert(&mut self, value: T) -> Occupied<'_, T> {
+        self.dirty = true;
+        self.slot.emplace(value)
+    }
+
+    /// Remove the value from this slot, if any. This sets the dirty flag if
+    /// the slot was occupied.
+    #[inline]
+    pub const fn take(&mut self) -> Option<T> {
+        if self.slot.is_some() {
+            self.dirty = true;
+        }
+
+        self.slot.take()
+    }
+
+    /// Call `body` with an [`Entry`] for this slot. Afterwards, the dirty
+    /// flag is set if the slot was occupied before or after (since `body`
+    /// may have changed it).
+    #[inline]
+    pub fn with_entry<R>(&mut self, body: impl FnOnce(Entry<'_, T>) -> R) -> R {
+        let before = self.slot.is_some();
+        let result = body(examine(&mut self.slot));
+
+        if before || self.slot.is_some() {
+            self.dirty = true;
+        }
+
+        result
+    }
+
+    /// Consume this slot, returning the value, if any.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Option<T> {
+        self.slot
+    }
+}
+
+impl<T: 'static> DynSlot for DirtySlot<T> {
+    #[inline]
+    fn is_occupied(&self) -> bool {
+        self.slot.is_some()
+    }
+
+    /// Remove the value from this slot, setting the dirty flag if it was
+    /// occupied.
+    #[inline]
+    fn clear(&mut self) {
+        self.take();
+    }
+}
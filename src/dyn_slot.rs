@@ -0,0 +1,96 @@
+/*!
+A dyn-compatible trait for slots of any type, allowing heterogeneous
+collections of slots.
+*/
+
+use core::{any::Any, fmt};
+
+use crate::{Entry, examine};
+
+/**
+A type-erased slot, which may or may not be occupied. This is implemented by
+[`Option`] and by the slot types in this crate, and it's dyn-compatible, so
+you can keep collections of differently-typed slots (for instance, in a
+`Vec<Box<dyn DynSlot>>`) and perform bulk operations over them. Use
+[`downcast_mut`][dyn DynSlot::downcast_mut] or [`entry`][dyn DynSlot::entry]
+to get back to the concrete type.
+
+# Example
+
+```
+use occupied::DynSlot;
+
+let mut slots: Vec<Box<dyn DynSlot>> = vec![
+    Box::new(Some(1)),
+    Box::new(None::<String>),
+    Box::new(Some("hello")),
+];
+
+assert_eq!(slots.iter().filter(|slot| slot.is_occupied()).count(), 2);
+
+slots[1].entry::<String>().unwrap().or_insert_with(String::new);
+assert_eq!(slots.iter().filter(|slot| slot.is_occupied()).count(), 3);
+
+slots.iter_mut().for_each(|slot| slot.clear());
+assert!(slots.iter().all(|slot| !slot.is_occupied()));
+```
+*/
+pub trait DynSlot: Any {
+    /// Returns true if this slot currently holds a value.
+    #[must_use]
+    fn is_occupied(&self) -> bool;
+
+    /// Remove (and drop) any value in this slot.
+    fn clear(&mut self);
+}
+
+impl dyn DynSlot {
+    /// Returns true if the concrete type of this slot is `S`.
+    #[inline]
+    #[must_use]
+    pub fn is<S: DynSlot>(&self) -> bool {
+        (self as &dyn Any).is::<S>()
+    }
+
+    /// Get a reference to this slot as its concrete type, if it's an `S`.
+    #[inline]
+    #[must_use]
+    pub fn downcast_ref<S: DynSlot>(&self) -> Option<&S> {
+        (self as &dyn Any).downcast_ref()
+    }
+
+    /// Get a mutable reference to this slot as its concrete type, if it's an
+    /// `S`.
+    #[inline]
+    #[must_use]
+    pub fn downcast_mut<S: DynSlot>(&mut self) -> Option<&mut S> {
+        (self as &mut dyn Any).downcast_mut()
+    }
+
+    /// Get an [`Entry`] for this slot, if it's an `Option<T>`.
+    #[inline]
+    #[must_use]
+    pub fn entry<T: 'static>(&mut self) -> Option<Entry<'_, T>> {
+        self.downcast_mut().map(examine)
+    }
+}
+
+impl fmt::Debug for dyn DynSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynSlot")
+            .field("occupied", &self.is_occupied())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static> DynSlot for Option<T> {
+    #[inline]
+    fn is_occupied(&self) -> bool {
+        self.is_some()
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        *self = None;
+    }
+}
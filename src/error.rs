@@ -0,0 +1,156 @@
+/*!
+Error types for fallible conversions into [`Occupied`] and [`Vacant`]
+references, which hand back a proof of the option's actual state.
+*/
+
+use core::error::Error;
+use core::fmt;
+
+use crate::{Entry, Occupied, Vacant, examine};
+
+/**
+Error returned when an [`Occupied`] reference was requested, but the option
+was vacant. It contains a [`Vacant`] reference to the option, so that the
+caller can still insert into it.
+
+# Example
+
+```
+use occupied::{Occupied, VacantError};
+
+fn first_word(option: &mut Option<String>) -> Result<&str, VacantError<'_, String>> {
+    let occupied = Occupied::try_from(option)?;
+    Ok(occupied.into_ref().split(' ').next().unwrap_or(""))
+}
+
+let mut opt = Some(String::from("hello world"));
+assert_eq!(first_word(&mut opt).ok(), Some("hello"));
+
+let mut opt = None;
+let err = first_word(&mut opt).unwrap_err();
+assert_eq!(err.to_string(), "option is vacant");
+
+err.into_vacant().insert(String::from("default"));
+assert_eq!(opt.as_deref(), Some("default"));
+```
+*/
+pub struct VacantError<'a, T> {
+    vacant: Vacant<'a, T>,
+}
+
+impl<'a, T> VacantError<'a, T> {
+    /// Create a new [`VacantError`] holding a [`Vacant`] reference.
+    #[inline]
+    #[must_use]
+    pub(crate) const fn new(vacant: Vacant<'a, T>) -> Self {
+        Self { vacant }
+    }
+
+    /// Recover the [`Vacant`] reference to the option.
+    #[inline]
+    #[must_use]
+    pub const fn into_vacant(self) -> Vacant<'a, T> {
+        self.vacant
+    }
+}
+
+impl<T> fmt::Debug for VacantError<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VacantError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for VacantError<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("option is vacant")
+    }
+}
+
+impl<T> Error for VacantError<'_, T> {}
+
+/**
+Error returned when a [`Vacant`] reference was requested, but the option was
+occupied. It contains an [`Occupied`] reference to the option, so that the
+caller can still access the existing item.
+
+# Example
+
+```
+use occupied::Vacant;
+
+let mut opt = Some(1);
+
+let err = Vacant::try_from(&mut opt).unwrap_err();
+assert_eq!(*err.get(), 1);
+assert_eq!(err.to_string(), "option is occupied");
+
+assert_eq!(err.into_occupied().take(), 1);
+assert!(Vacant::try_from(&mut opt).is_ok());
+```
+*/
+pub struct OccupiedError<'a, T> {
+    occupied: Occupied<'a, T>,
+}
+
+impl<'a, T> OccupiedError<'a, T> {
+    /// Create a new [`OccupiedError`] holding an [`Occupied`] reference.
+    #[inline]
+    #[must_use]
+    pub(crate) const fn new(occupied: Occupied<'a, T>) -> Self {
+        Self { occupied }
+    }
+
+    /// Get a reference to the item already in the option.
+    #[inline]
+    #[must_use]
+    pub const fn get(&self) -> &T {
+        self.occupied.get()
+    }
+
+    /// Recover the [`Occupied`] reference to the option.
+    #[inline]
+    #[must_use]
+    pub const fn into_occupied(self) -> Occupied<'a, T> {
+        self.occupied
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OccupiedError<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedError")
+            .field("occupied", &self.occupied)
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for OccupiedError<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("option is occupied")
+    }
+}
+
+impl<T: fmt::Debug> Error for OccupiedError<'_, T> {}
+
+impl<'a, T> TryFrom<&'a mut Option<T>> for Occupied<'a, T> {
+    type Error = VacantError<'a, T>;
+
+    #[inline]
+    fn try_from(option: &'a mut Option<T>) -> Result<Self, Self::Error> {
+        match examine(option) {
+            Entry::Occupied(occupied) => Ok(occupied),
+            Entry::Vacant(vacant) => Err(VacantError::new(vacant)),
+        }
+    }
+}
+
+impl<'a, T> TryFrom<&'a mut Option<T>> for Vacant<'a, T> {
+    type Error = OccupiedError<'a, T>;
+
+    #[inline]
+    fn try_from(option: &'a mut Option<T>) -> Result<Self, Self::Error> {
+        match examine(option) {
+            Entry::Occupied(occupied) => Err(OccupiedError::new(occupied)),
+            Entry::Vacant(vacant) => Ok(vacant),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+/*!
+A slot whose contents expire after a fixed time-to-live.
+*/
+
+use std::time::{Duration, Instant};
+
+use crate::{DynSlot, Entry, Occupied, OptionExt as _, examine};
+
+/**
+An optional value that expires a fixed duration (the time-to-live, or TTL)
+after it was inserted. Once a value has expired, the slot treats itself as
+vacant, and the stale value is dropped lazily, the next time the slot is
+accessed. A TTL too long to be represented as an [`Instant`] (such as
+[`Duration::MAX`]) means values never expire.
+
+# Example
+
+```
+use std::time::Duration;
+use occupied::ExpiringSlot;
+
+let mut token = ExpiringSlot::new(Duration::from_secs(3600));
+token.insert("secret");
+assert_eq!(token.peek_some().map(|token| *token.get()), Some("secret"));
+
+// A zero TTL means values expire immediately
+let mut token = ExpiringSlot::new(Duration::ZERO);
+token.insert("secret");
+assert!(token.peek_some().is_none());
+
+// A TTL past the end of time means values never expire
+let mut token = ExpiringSlot::new(Duration::MAX);
+token.insert("secret");
+assert_eq!(token.deadline(), None);
+assert!(token.refresh());
+assert_eq!(token.take(), Some("secret"));
+```
+*/
+#[derive(Debug, Clone)]
+pub struct ExpiringSlot<T> {
+    slot: Option<T>,
+
+    // Only meaningful while `slot` is `Some`. `None` means the value never
+    // expires.
+    deadline: Option<Instant>,
+    ttl: Duration,
+}
+
+impl<T> ExpiringSlot<T> {
+    /// Create a new, vacant [`ExpiringSlot`], where inserted values expire
+    /// `ttl` after they're inserted.
+    #[inline]
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            slot: None,
+            deadline: None,
+            ttl,
+        }
+    }
+
+    /// Get the time-to-live of values inserted into this slot.
+    #[inline]
+    #[must_use]
+    pub const fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Change the time-to-live of values inserted into this slot. This
+    /// doesn't affect the deadline of the current value, if any, until it's
+    /// [refreshed][Self::refresh].
+    #[inline]
+    pub const fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+
+    /// Get the instant when the current value expires, or [`None`] if the
+    /// slot is vacant or the value never expires. The value may have
+    /// already expired.
+    #[inline]
+    #[must_use]
+    pub const fn deadline(&self) -> Option<Instant> {
+        match self.slot {
+            Some(_) => self.deadline,
+            None => None,
+        }
+    }
+
+    /// Compute the deadline for a value inserted now, or [`None`] if the TTL
+    /// is too long to be represented, meaning that the value never expires.
+    #[inline]
+    fn fresh_deadline(&self) -> Option<Instant> {
+        Instant::now().checked_add(self.ttl)
+    }
+
+    /// Returns true if the current deadline has passed.
+    #[inline]
+    fn is_expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Drop the current value, if it has expired.
+    #[inline]
+    pub fn expire(&mut self) {
+        if self.is_expired()
+            && let Some(occupied) = self.slot.peek_some()
+        {
+            occupied.take();
+        }
+    }
+
+    /// Insert a value into this slot with a fresh deadline, replacing any
+    /// existing value, and return an [`Occupied`] reference to it.
+    #[inline]
+    pub fn insert(&mut self, value: T) -> Occupied<'_, T> {
+        self.deadline = self.fresh_deadline();
+        self.slot.emplace(value)
+    }
+
+    /// Try to get an [`Occupied`] reference to the value in this slot.
+    /// Returns [`None`] if the slot is vacant or the value has expired.
+    #[inline]
+    #[must_use]
+    pub fn peek_some(&mut self) -> Option<Occupied<'_, T>> {
+        self.expire();
+        self.slot.peek_some()
+    }
+
+    /**
+    Get an [`Entry`] for this slot, after dropping the current value if it
+    has expired. If the entry is vacant, the deadline is reset as though a
+    value were inserted now, so that a value inserted through the entry gets
+    a full time-to-live.
+    */
+    #[inline]
+    #[must_use]
+    pub fn entry(&mut self) -> Entry<'_, T> {
+        self.expire();
+
+        if self.slot.is_none() {
+            self.deadline = self.fresh_deadline();
+        }
+
+        examine(&mut self.slot)
+    }
+
+    /**
+    Reset the deadline of the current value, as though it were inserted
+    now. Returns false (and does nothing) if the slot is vacant or the value
+    has already expired.
+
+    # Example
+
+    ```
+    use std::time::Duration;
+    use occupied::ExpiringSlot;
+
+    let mut slot = ExpiringSlot::new(Duration::ZERO);
+    assert!(!slot.refresh());
+
+    slot.set_ttl(Duration::from_secs(3600));
+    slot.insert(1);
+    assert!(slot.refresh());
+    ```
+    */
+    #[inline]
+    pub fn refresh(&mut self) -> bool {
+        self.expire();
+
+        match self.slot {
+            Some(_) => {
+                self.deadline = self.fresh_deadline();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the value from this slot, if it hasn't expired.
+    #[inline]
+    pub fn take(&mut self) -> Option<T> {
+        self.expire();
+        self.slot.take()
+    }
+}
+
+impl<T: 'static> DynSlot for ExpiringSlot<T> {
+    /// An [`ExpiringSlot`] is occupied if it holds a value that hasn't
+    /// expired.
+    #[inline]
+    fn is_occupied(&self) -> bool {
+        self.slot.is_some() && !self.is_expired()
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.slot = None;
+    }
+}
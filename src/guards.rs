@@ -0,0 +1,315 @@
+/*!
+RAII guards over proofs, which restore some state to the underlying option
+when they're dropped.
+*/
+
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{Occupied, Vacant};
+
+/**
+A guard for an item that was temporarily inserted into an option with
+[`Vacant::insert_scoped`]. The guard derefs to the item, and removes it from
+the option (dropping it) when the guard is dropped, leaving the option vacant
+again.
+*/
+#[must_use = "dropping a `ScopedInsert` immediately removes the item"]
+pub struct ScopedInsert<'a, T> {
+    // This is always `Some` until the guard is consumed
+    occupied: Option<Occupied<'a, T>>,
+}
+
+impl<'a, T> ScopedInsert<'a, T> {
+    /**
+    Remove the item from the option, and return it, rather than dropping it.
+    The option is left vacant.
+    */
+    #[inline]
+    #[must_use]
+    pub fn into_inner(mut self) -> T {
+        // Safety: `occupied` is always `Some` until the guard is consumed
+        unwrap_unchecked!(self.occupied.take()).take()
+    }
+
+    /**
+    Keep the item in the option, rather than removing it when the guard is
+    dropped, and return an [`Occupied`] reference to it.
+    */
+    #[inline]
+    #[must_use]
+    pub fn keep(mut self) -> Occupied<'a, T> {
+        // Safety: `occupied` is always `Some` until the guard is consumed
+        unwrap_unchecked!(self.occupied.take())
+    }
+}
+
+impl<T> Deref for ScopedInsert<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        // Safety: `occupied` is always `Some` until the guard is consumed
+        unwrap_unchecked!(self.occupied.as_ref()).get()
+    }
+}
+
+impl<T> DerefMut for ScopedInsert<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: `occupied` is always `Some` until the guard is consumed
+        unwrap_unchecked!(self.occupied.as_mut()).get_mut()
+    }
+}
+
+impl<T> Drop for ScopedInsert<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(occupied) = self.occupied.take() {
+            occupied.take();
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ScopedInsert<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ScopedInsert").field(&**self).finish()
+    }
+}
+
+/**
+A guard for an item that was temporarily removed from an option with
+[`Occupied::loan`]. The guard owns the item and derefs to it, and puts it back
+into the option when the guard is dropped, including during a panic.
+*/
+#[must_use = "dropping a `Loan` immediately puts the item back"]
+pub struct Loan<'a, T> {
+    // This is always `Some` until the guard is consumed
+    loan: Option<(Vacant<'a, T>, T)>,
+}
+
+impl<'a, T> Loan<'a, T> {
+    /**
+    Take ownership of the loaned item, and return it along with a [`Vacant`]
+    reference to the option it came from, which can be used to put an item
+    back. The item is no longer automatically returned; if the [`Vacant`]
+    reference is dropped, the option is left vacant.
+    */
+    #[inline]
+    #[must_use]
+    pub fn take_loaned(mut self) -> (T, Vacant<'a, T>) {
+        // Safety: `loan` is always `Some` until the guard is consumed
+        let (vacant, item) = unwrap_unchecked!(self.loan.take());
+        (item, vacant)
+    }
+
+    /**
+    End the loan, putting the item back into the option, and return an
+    [`Occupied`] reference to it.
+    */
+    #[inline]
+    #[must_use]
+    pub fn restore(mut self) -> Occupied<'a, T> {
+        // Safety: `loan` is always `Some` until the guard is consumed
+        let (vacant, item) = unwrap_unchecked!(self.loan.take());
+        vacant.insert(item)
+    }
+}
+
+impl<T> Deref for Loan<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        // Safety: `loan` is always `Some` until the guard is consumed
+        &unwrap_unchecked!(self.loan.as_ref()).1
+    }
+}
+
+impl<T> DerefMut for Loan<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: `loan` is always `Some` until the guard is consumed
+        &mut unwrap_unchecked!(self.loan.as_mut()).1
+    }
+}
+
+impl<T> Drop for Loan<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some((vacant, item)) = self.loan.take() {
+            vacant.insert(item);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Loan<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Loan").field(&**self).finish()
+    }
+}
+
+impl<'a, T> Occupied<'a, T> {
+    /**
+    Temporarily remove the item from the option, returning a [`Loan`] guard
+    which owns it. The option is vacant for the duration of the loan, and the
+    item is put back when the guard is dropped, even if the thread panics in
+    the meantime.
+
+    # Example
+
+    ```
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use occupied::OptionExt as _;
+
+    let mut config = Some(vec![1, 2, 3]);
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let mut loan = config.peek_some().unwrap().loan();
+        loan.push(4);
+        panic!("oops");
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(config, Some(vec![1, 2, 3, 4]));
+
+    let (items, vacant) = config.peek_some().unwrap().loan().take_loaned();
+    vacant.insert(items.into_iter().rev().collect());
+    assert_eq!(config, Some(vec![4, 3, 2, 1]));
+    ```
+    */
+    #[inline]
+    pub fn loan(self) -> Loan<'a, T> {
+        Loan {
+            loan: Some(self.extract()),
+        }
+    }
+}
+
+/**
+A guard for an insert into a vacant option which was deferred with
+[`Vacant::defer_insert`] or [`Vacant::defer_insert_with`]. The item is
+inserted when the guard is dropped, unless the insert is
+[cancelled][Self::cancel].
+*/
+#[must_use = "dropping a `DeferredInsert` immediately inserts the item"]
+pub struct DeferredInsert<'a, T, F: FnOnce() -> T> {
+    // This is `None` once the guard is consumed
+    pending: Option<(Vacant<'a, T>, F)>,
+}
+
+impl<'a, T, F: FnOnce() -> T> DeferredInsert<'a, T, F> {
+    /// Cancel the insert, and return the [`Vacant`] reference to the option.
+    #[inline]
+    pub fn cancel(mut self) -> Vacant<'a, T> {
+        // Safety: `pending` is always `Some` until the guard is consumed
+        unwrap_unchecked!(self.pending.take()).0
+    }
+
+    /// Perform the insert now, rather than waiting for the guard to be
+    /// dropped, and return an [`Occupied`] reference to the option.
+    #[inline]
+    pub fn commit(mut self) -> Occupied<'a, T> {
+        // Safety: `pending` is always `Some` until the guard is consumed
+        let (vacant, item) = unwrap_unchecked!(self.pending.take());
+        vacant.insert(item())
+    }
+}
+
+impl<T, F: FnOnce() -> T> Drop for DeferredInsert<'_, T, F> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some((vacant, item)) = self.pending.take() {
+            vacant.insert(item());
+        }
+    }
+}
+
+impl<T, F: FnOnce() -> T> fmt::Debug for DeferredInsert<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeferredInsert").finish_non_exhaustive()
+    }
+}
+
+impl<'a, T> Vacant<'a, T> {
+    /**
+    Insert an item into the [`Vacant`] option for the duration of a scope.
+    The returned guard derefs to the item, and removes it from the option
+    again when it's dropped. This is useful for temporary registrations, like
+    reentrancy flags or "current item" pointers.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut current = None;
+
+    {
+        let mut guard = current.peek_empty().unwrap().insert_scoped(1);
+        *guard += 1;
+        assert_eq!(*guard, 2);
+    }
+
+    assert_eq!(current, None);
+
+    let guard = current.peek_empty().unwrap().insert_scoped(3);
+    assert_eq!(guard.into_inner(), 3);
+    assert_eq!(current, None);
+    ```
+    */
+    #[inline]
+    pub fn insert_scoped(self, item: T) -> ScopedInsert<'a, T> {
+        ScopedInsert {
+            occupied: Some(self.insert(item)),
+        }
+    }
+
+    /**
+    Defer inserting `item` into the option until the returned guard is
+    dropped, unless the insert is [cancelled][DeferredInsert::cancel] first.
+    This is useful for "reserve now, commit at the end of the scope" flows,
+    where code in between might return early.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    fn register(slot: &mut Option<&'static str>, name: &'static str) -> Result<(), ()> {
+        let pending = slot.peek_empty().ok_or(())?.defer_insert(name);
+
+        if name.is_empty() {
+            pending.cancel();
+            return Err(());
+        }
+
+        // Any early return from here on still commits the insert
+        Ok(())
+    }
+
+    let mut slot = None;
+
+    assert_eq!(register(&mut slot, ""), Err(()));
+    assert_eq!(slot, None);
+
+    assert_eq!(register(&mut slot, "main"), Ok(()));
+    assert_eq!(slot, Some("main"));
+    ```
+    */
+    #[inline]
+    pub fn defer_insert(self, item: T) -> DeferredInsert<'a, T, impl FnOnce() -> T> {
+        self.defer_insert_with(move || item)
+    }
+
+    /// Like [`defer_insert`][Self::defer_insert], but the item is only
+    /// created with `f` when the insert actually happens.
+    #[inline]
+    pub fn defer_insert_with<F: FnOnce() -> T>(self, f: F) -> DeferredInsert<'a, T, F> {
+        DeferredInsert {
+            pending: Some((self, f)),
+        }
+    }
+}
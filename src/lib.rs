@@ -1,4 +1,5 @@
 #![no_std]
+#![cfg_attr(feature = "forbid-unsafe", deny(unsafe_code))]
 
 /*!
 `occupied` provides compile-time guaranteed ways to interact with inserting
@@ -52,9 +53,97 @@ opts[3] = Some(4);
 assert_eq!(try_unwrap_all(&mut opts), Some([1, 2, 3, 4]));
 assert_eq!(opts, [None, None, None, None]);
 ```
+
+# Features
+
+- `alloc`: enable support for types from the `alloc` crate, such as proofs
+  over a `Cow` (see `CowExt`).
+- `derive`: enable `#[derive(OverlayOptions)]`, `#[derive(SlotEnum)]`, and
+  `#[occupied::accessors]` for structs of optional fields.
+- `std`: enable support for types from the standard library, such as
+  `ExpiringSlot`, the `oneshot` channel, and `BoundedQueue`. Implies
+  `alloc`.
+- `test-util`: enable `RecordingSlot`, a slot that logs every operation
+  performed on it, for use in tests. Implies `alloc`.
+- `ufmt`: implement [`ufmt`](https://docs.rs/ufmt)'s `uDebug` and `uDisplay`
+  for the proof and slot types, as a lightweight alternative to `core::fmt`.
+- `subtle`: enable constant-time occupancy queries (see `CtOptionExt`) and
+  constant-time selection between the items of `Occupied` options, using
+  [`subtle`](https://docs.rs/subtle).
+- `forbid-unsafe`: replace the unchecked unwraps used internally with
+  checked equivalents, so that the crate contains no `unsafe` code apart from
+  the declarations of the `new_unchecked` constructors (which remain `unsafe`
+  so that the public API is identical). This costs a few redundant branches.
 */
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::borrow::Borrow;
+use core::fmt;
+#[cfg(not(feature = "forbid-unsafe"))]
 use core::hint::unreachable_unchecked;
+use core::ops::{Deref, DerefMut};
+
+/// Unwrap an [`Option`] that is known to be [`Some`]. Normally this is
+/// [`Option::unwrap_unchecked`]; with the `forbid-unsafe` feature it's a
+/// regular checked unwrap. Callers are still responsible for upholding the
+/// invariant and should document it with a `Safety` comment.
+macro_rules! unwrap_unchecked {
+    ($option:expr) => {{
+        #[cfg(not(feature = "forbid-unsafe"))]
+        let item = unsafe { $option.unwrap_unchecked() };
+
+        #[cfg(feature = "forbid-unsafe")]
+        let item = $option.unwrap();
+
+        item
+    }};
+}
+
+/// Mark a branch as unreachable. Normally this is
+/// [`unreachable_unchecked`][core::hint::unreachable_unchecked]; with the
+/// `forbid-unsafe` feature it panics instead. Callers are still responsible
+/// for ensuring the branch is never taken.
+#[cfg_attr(not(feature = "alloc"), allow(unused_macros))]
+macro_rules! unreachable_unchecked {
+    () => {{
+        #[cfg(not(feature = "forbid-unsafe"))]
+        unsafe {
+            core::hint::unreachable_unchecked()
+        }
+
+        #[cfg(feature = "forbid-unsafe")]
+        unreachable!()
+    }};
+}
+
+/// Create a proof type (such as [`Occupied`] or [`Vacant`]) without checking
+/// its invariant. Normally this calls the `unsafe` `new_unchecked`
+/// constructor; with the `forbid-unsafe` feature it calls the safe
+/// `new_trusted` constructor instead, which is sound because nothing relies
+/// on the invariant for memory safety in that mode. Callers are still
+/// responsible for upholding the invariant.
+macro_rules! new_unchecked {
+    ($Type:ident, $option:expr) => {{
+        #[cfg(not(feature = "forbid-unsafe"))]
+        let proof = unsafe { $Type::new_unchecked($option) };
+
+        #[cfg(feature = "forbid-unsafe")]
+        let proof = $Type::new_trusted($option);
+
+        proof
+    }};
+}
+
+/// Mark the calling branch as unlikely to be taken. This is a stable
+/// stand-in for `core::hint::cold_path`: calls to a `#[cold]` function are
+/// assumed to be rare, which the optimizer propagates to the branch.
+#[cold]
+#[inline(never)]
+const fn cold() {}
 
 /// Hide implementation details in a submodule, to contain the sites where
 /// `Occupied.option` and `Vacant.option` can be accessed directly (because
@@ -66,8 +155,37 @@ mod internals {
         A reference to an [`Option`] that is statically guaranteed to be occupied,
         meaning we can [`.take()`][Occupied::take] the object out unconditionally,
         and infallibly, leaving a [`None`] in its place.
+
+        # Comparisons
+
+        [`Occupied`] references are compared and hashed by the items in their
+        options, so that collections of them can be sorted, deduplicated, or
+        used as keys:
+
+        ```
+        use occupied::OptionExt as _;
+
+        let mut slots = [Some(3), None, Some(1), Some(2)];
+
+        let mut occupied: Vec<_> = slots.iter_mut().filter_map(|slot| slot.peek_some()).collect();
+        occupied.sort();
+
+        let order: Vec<i32> = occupied.into_iter().map(|occupied| occupied.take()).collect();
+        assert_eq!(order, [1, 2, 3]);
+        ```
+
+        They can also be compared directly with items of the same type:
+
+        ```
+        use occupied::OptionExt as _;
+
+        let mut opt = Some(5);
+        let occupied = opt.peek_some().unwrap();
+
+        assert!(occupied == 5);
+        assert!(occupied < 10);
+        ```
         */
-        #[derive(Debug)]
         pub struct Occupied<'a, T> {
             option: &'a mut Option<T>,
         }
@@ -83,11 +201,22 @@ mod internals {
             */
             #[inline(always)]
             #[must_use]
+            #[allow(unsafe_code)]
             pub const unsafe fn new_unchecked(option: &'a mut Option<T>) -> Self {
                 debug_assert!(option.is_some());
                 Self { option }
             }
 
+            /// Safe equivalent of [`new_unchecked`][Self::new_unchecked],
+            /// used internally when `forbid-unsafe` is enabled.
+            #[cfg(feature = "forbid-unsafe")]
+            #[inline(always)]
+            #[must_use]
+            pub(crate) const fn new_trusted(option: &'a mut Option<T>) -> Self {
+                debug_assert!(option.is_some());
+                Self { option }
+            }
+
             /**
             Get an immutable reference to the data in the referenced option.
 
@@ -106,7 +235,7 @@ mod internals {
             #[must_use]
             pub const fn get(&self) -> &T {
                 debug_assert!(self.option.is_some());
-                unsafe { self.option.as_ref().unwrap_unchecked() }
+                unwrap_unchecked!(self.option.as_ref())
             }
 
             /**
@@ -129,7 +258,7 @@ mod internals {
             #[must_use]
             pub const fn get_mut(&mut self) -> &mut T {
                 debug_assert!(self.option.is_some());
-                unsafe { self.option.as_mut().unwrap_unchecked() }
+                unwrap_unchecked!(self.option.as_mut())
             }
 
             /**
@@ -155,6 +284,43 @@ mod internals {
             pub const fn into_inner(self) -> &'a mut Option<T> {
                 self.option
             }
+
+            /// Get a raw pointer to the referenced [`Option`], for identity
+            /// comparisons. The pointer must not be used to write to the
+            /// option while this [`Occupied`] is alive.
+            #[inline(always)]
+            #[must_use]
+            pub const fn as_ptr(&self) -> *const Option<T> {
+                &*self.option
+            }
+
+            /**
+            Returns true if this [`Occupied`] refers to the option at
+            `option`, by pointer identity. Since the [`Occupied`] holds the
+            only mutable borrow of its option, the pointer to compare against
+            must be taken beforehand (or from another proof, with
+            [`as_ptr`][Self::as_ptr]).
+
+            # Example
+
+            ```
+            use occupied::OptionExt as _;
+
+            let mut a = Some(1);
+            let b = Some(1);
+
+            let a_ptr: *const Option<i32> = &a;
+            let occupied = a.peek_some().unwrap();
+
+            assert!(occupied.refers_to(a_ptr));
+            assert!(!occupied.refers_to(&b));
+            ```
+            */
+            #[inline(always)]
+            #[must_use]
+            pub fn refers_to(&self, option: *const Option<T>) -> bool {
+                core::ptr::eq(self.as_ptr(), option)
+            }
         }
     }
 
@@ -180,11 +346,22 @@ mod internals {
              */
             #[inline(always)]
             #[must_use]
+            #[allow(unsafe_code)]
             pub const unsafe fn new_unchecked(option: &'a mut Option<T>) -> Self {
                 debug_assert!(option.is_none());
                 Self { option }
             }
 
+            /// Safe equivalent of [`new_unchecked`][Self::new_unchecked],
+            /// used internally when `forbid-unsafe` is enabled.
+            #[cfg(feature = "forbid-unsafe")]
+            #[inline(always)]
+            #[must_use]
+            pub(crate) const fn new_trusted(option: &'a mut Option<T>) -> Self {
+                debug_assert!(option.is_none());
+                Self { option }
+            }
+
             /**
             Get a mutable reference to the underlying [`Option`]. This destroys
             `self`, because we lose the guarantee that the option is vacant.
@@ -212,14 +389,188 @@ mod internals {
             pub const fn into_inner(self) -> &'a mut Option<T> {
                 self.option
             }
+
+            /// Get a raw pointer to the referenced [`Option`], for identity
+            /// comparisons. The pointer must not be used to write to the
+            /// option while this [`Vacant`] is alive.
+            #[inline(always)]
+            #[must_use]
+            pub const fn as_ptr(&self) -> *const Option<T> {
+                &*self.option
+            }
+
+            /// Returns true if this [`Vacant`] refers to the option at
+            /// `option`, by pointer identity.
+            #[inline(always)]
+            #[must_use]
+            pub fn refers_to(&self, option: *const Option<T>) -> bool {
+                core::ptr::eq(self.as_ptr(), option)
+            }
+        }
+    }
+
+    mod occupied_ref {
+        /**
+        A shared reference to an [`Option`] that is statically guaranteed to
+        be occupied. This is the read-only counterpart to
+        [`Occupied`][crate::Occupied]: since it only needs `&Option<T>`, it
+        can be created wherever shared access is available, and it's [`Copy`],
+        so it can be freely passed around as a witness that the option is
+        [`Some`].
+        */
+        pub struct OccupiedRef<'a, T> {
+            option: &'a Option<T>,
+        }
+
+        impl<'a, T> OccupiedRef<'a, T> {
+            /**
+            Create a new [`OccupiedRef`], referencing an [`Option`] that is
+            definitely [`Some`].
+
+            # Safety
+
+            The `option` parameter MUST be [`Some`].
+            */
+            #[inline(always)]
+            #[must_use]
+            #[allow(unsafe_code)]
+            pub const unsafe fn new_unchecked(option: &'a Option<T>) -> Self {
+                debug_assert!(option.is_some());
+                Self { option }
+            }
+
+            /// Safe equivalent of [`new_unchecked`][Self::new_unchecked],
+            /// used internally when `forbid-unsafe` is enabled.
+            #[cfg(feature = "forbid-unsafe")]
+            #[inline(always)]
+            #[must_use]
+            pub(crate) const fn new_trusted(option: &'a Option<T>) -> Self {
+                debug_assert!(option.is_some());
+                Self { option }
+            }
+
+            /**
+            Get a reference to the data in the referenced option.
+
+            # Example
+
+            ```
+            use occupied::OccupiedRef;
+
+            let opt = Some("hello");
+            let occupied = OccupiedRef::new(&opt).unwrap();
+
+            assert_eq!(*occupied.get(), "hello");
+            ```
+            */
+            #[inline(always)]
+            #[must_use]
+            pub const fn get(&self) -> &'a T {
+                debug_assert!(self.option.is_some());
+                unwrap_unchecked!(self.option.as_ref())
+            }
+
+            /// Get a reference to the data in the referenced option, with the
+            /// original lifetime.
+            #[inline(always)]
+            #[must_use]
+            pub const fn into_ref(self) -> &'a T {
+                self.get()
+            }
+
+            /// Get a reference to the underlying [`Option`].
+            #[inline(always)]
+            #[must_use]
+            pub const fn into_inner(self) -> &'a Option<T> {
+                self.option
+            }
         }
     }
 
     pub use occupied::Occupied;
+    pub use occupied_ref::OccupiedRef;
     pub use vacant::Vacant;
 }
 
-pub use internals::{Occupied, Vacant};
+pub use internals::{Occupied, OccupiedRef, Vacant};
+
+#[cfg(any(target_has_atomic = "64", target_has_atomic = "ptr"))]
+mod atomic;
+mod branded;
+mod claim;
+mod dirty;
+mod dyn_slot;
+mod error;
+mod guards;
+mod lookahead;
+mod nested;
+mod overlay;
+mod pending;
+pub mod pin;
+mod sequence;
+mod slice;
+mod undo;
+mod versioned;
+mod watched;
+
+#[cfg(feature = "alloc")]
+mod boxed;
+#[cfg(feature = "alloc")]
+mod cow;
+#[cfg(feature = "subtle")]
+mod ct;
+#[cfg(feature = "std")]
+mod expiring;
+#[cfg(feature = "std")]
+pub mod oneshot;
+#[cfg(feature = "std")]
+mod queue;
+#[cfg(feature = "test-util")]
+mod recording;
+#[cfg(feature = "ufmt")]
+mod ufmt_impls;
+#[cfg(feature = "alloc")]
+mod unique;
+#[cfg(feature = "alloc")]
+mod weak;
+
+#[cfg(target_has_atomic = "64")]
+pub use atomic::AtomicOptionU32;
+#[cfg(target_has_atomic = "ptr")]
+pub use atomic::AtomicOptionUsize;
+pub use branded::{BrandedSlots, OccupiedIndex, brand_slots};
+pub use claim::{TuplePeek, peek_all, try_zip, try_zip3};
+pub use dirty::DirtySlot;
+pub use dyn_slot::DynSlot;
+pub use error::{OccupiedError, VacantError};
+pub use guards::{DeferredInsert, Loan, ScopedInsert};
+pub use lookahead::LookaheadBuffer;
+pub use nested::{NestedEntry, examine_nested};
+pub use overlay::OverlayOptions;
+pub use pending::PendingSlot;
+pub use sequence::SequenceSlots;
+pub use slice::{OccupancyRun, OccupiedSlice, SliceOptionExt, VacantSlice};
+pub use undo::UndoSlot;
+pub use versioned::VersionedSlot;
+pub use watched::{SlotEvent, WatchedSlot};
+
+#[cfg(feature = "derive")]
+pub use occupied_derive::{OverlayOptions, SlotEnum, accessors};
+
+#[cfg(feature = "alloc")]
+pub use cow::{CowBorrowed, CowExt, CowOwned};
+#[cfg(feature = "subtle")]
+pub use ct::CtOptionExt;
+#[cfg(feature = "std")]
+pub use expiring::ExpiringSlot;
+#[cfg(feature = "std")]
+pub use oneshot::oneshot;
+#[cfg(feature = "std")]
+pub use queue::BoundedQueue;
+#[cfg(feature = "test-util")]
+pub use recording::{RecordingSlot, SlotOp, SlotRecord};
+#[cfg(feature = "alloc")]
+pub use weak::{Upgrade, WeakSlot, prune_dead, prune_dead_with};
 
 impl<'a, T> Occupied<'a, T> {
     /**
@@ -248,7 +599,101 @@ impl<'a, T> Occupied<'a, T> {
         debug_assert!(option.is_some());
 
         // Safety: the option in `Occupied` is guaranteed to be `Some`
-        unsafe { option.as_mut().unwrap_unchecked() }
+        unwrap_unchecked!(option.as_mut())
+    }
+
+    /**
+    Get a shared reference to the underlying value with the original
+    lifetime.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(String::from("hello"));
+    let name: &String = opt.peek_some().unwrap().into_ref();
+
+    assert_eq!(name, "hello");
+    ```
+    */
+    #[inline(always)]
+    #[must_use]
+    pub const fn into_ref(self) -> &'a T {
+        self.into_mut()
+    }
+
+    /// Wrap this back up into an [`Entry`], without re-examining the option.
+    #[inline(always)]
+    #[must_use]
+    pub const fn into_entry(self) -> Entry<'a, T> {
+        Entry::Occupied(self)
+    }
+
+    /**
+    Convert this into a shared [`OccupiedRef`] proof, with the original
+    lifetime. Unlike [`Occupied`], this is [`Copy`], so it can be handed to
+    any number of readers.
+
+    # Example
+
+    ```
+    use occupied::{OccupiedRef, OptionExt as _};
+
+    fn shout(name: OccupiedRef<'_, String>) -> String {
+        name.get().to_uppercase()
+    }
+
+    let mut name = Some(String::from("hello"));
+    let mut occupied = name.peek_some().unwrap();
+    occupied.push_str(", world");
+
+    let witness = occupied.downgrade();
+    assert_eq!(shout(witness), "HELLO, WORLD");
+    assert_eq!(witness.get().len(), 12);
+    ```
+    */
+    #[inline(always)]
+    #[must_use]
+    pub const fn downgrade(self) -> OccupiedRef<'a, T> {
+        let option: &'a Option<T> = self.into_inner();
+
+        // Safety: the option in `Occupied` is guaranteed to be `Some`
+        new_unchecked!(OccupiedRef, option)
+    }
+
+    /**
+    Get a slice containing the item in the option, which always has length
+    1. This mirrors [`Option::as_slice`].
+
+    Note that this shadows any `as_slice` method on `T` itself, which is
+    otherwise reachable through [`Deref`]; use [`get`][Self::get] to call it.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(5);
+    let occupied = opt.peek_some().unwrap();
+
+    assert_eq!(occupied.as_slice(), [5]);
+    ```
+    */
+    #[inline(always)]
+    #[must_use]
+    pub const fn as_slice(&self) -> &[T] {
+        core::slice::from_ref(self.get())
+    }
+
+    /**
+    Get a mutable slice containing the item in the option, which always has
+    length 1. This mirrors [`Option::as_mut_slice`].
+    */
+    #[inline(always)]
+    #[must_use]
+    pub const fn as_mut_slice(&mut self) -> &mut [T] {
+        core::slice::from_mut(self.get_mut())
     }
 
     /**
@@ -276,7 +721,7 @@ impl<'a, T> Occupied<'a, T> {
         debug_assert!(option.is_some());
 
         // Safety: option from `Occupied` is guaranteed to be `Some`.
-        unsafe { option.take().unwrap_unchecked() }
+        unwrap_unchecked!(option.take())
     }
 
     /**
@@ -291,115 +736,1340 @@ impl<'a, T> Occupied<'a, T> {
         debug_assert!(option.is_some());
 
         // Safety: option from an `Occupied` is guaranteed to be `Some`
-        let item = unsafe { option.take().unwrap_unchecked() };
+        let item = unwrap_unchecked!(option.take());
 
         // Safety: option is guaranteed to be `None` after `take`
-        (unsafe { Vacant::new_unchecked(option) }, item)
+        (new_unchecked!(Vacant, option), item)
     }
-}
 
-impl<T> AsRef<T> for Occupied<'_, T> {
-    fn as_ref(&self) -> &T {
-        self.get()
-    }
-}
+    /**
+    Move the item from this option into the [`Vacant`] option `dest`,
+    returning a [`Vacant`] reference to this option and an [`Occupied`]
+    reference to `dest`.
 
-impl<T> AsMut<T> for Occupied<'_, T> {
-    fn as_mut(&mut self) -> &mut T {
-        self.get_mut()
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut staging = Some("ready");
+    let mut output = None;
+
+    let (_, moved) = staging
+        .peek_some()
+        .unwrap()
+        .move_to(output.peek_empty().unwrap());
+
+    assert_eq!(*moved.get(), "ready");
+    assert_eq!(staging, None);
+    assert_eq!(output, Some("ready"));
+    ```
+    */
+    #[inline]
+    pub fn move_to<'b>(self, dest: Vacant<'b, T>) -> (Vacant<'a, T>, Occupied<'b, T>) {
+        let (vacant, item) = self.extract();
+        (vacant, dest.insert(item))
     }
-}
 
-impl<'a, T> Vacant<'a, T> {
     /**
-    Try to create a new [`Vacant`] instance, referencing an [`Option`] that is
-    definitely [`None`]. Returns [`None`] if the option is [`Some`].
-     */
-    #[inline(always)]
-    #[must_use]
-    pub const fn new(option: &'a mut Option<T>) -> Option<Self> {
-        match examine(option) {
-            Entry::Vacant(vacant) => Some(vacant),
-            Entry::Occupied(_) => None,
+    Remove the item from the option if `predicate` returns true for it,
+    leaving [`None`] in its place. Otherwise, return this [`Occupied`]
+    reference back, unchanged (apart from any changes made by `predicate`).
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(3);
+
+    let occupied = opt.peek_some().unwrap();
+    let occupied = occupied.take_if(|item| *item > 5).unwrap_err();
+
+    assert_eq!(occupied.take_if(|item| *item == 3).ok(), Some(3));
+    assert_eq!(opt, None);
+    ```
+    */
+    #[inline]
+    pub fn take_if(mut self, predicate: impl FnOnce(&mut T) -> bool) -> Result<T, Self> {
+        match predicate(self.get_mut()) {
+            true => Ok(self.take()),
+            false => Err(self),
         }
     }
 
     /**
-    Insert an item into the [`Vacant`] option, and return an [`Occupied`]
-    reference to the inserted item. This will be *slightly* more efficient
-    than [`Option::insert`], since [`Option::insert`] must check whether
-    the option is currently [`Some`] and destruct if so.
-    */
-    #[inline(always)]
-    pub const fn insert(self, item: T) -> Occupied<'a, T> {
-        let option = self.into_inner();
-        debug_assert!(option.is_none());
+    Take the item out of the option, leaving [`None`] in its place, and pass
+    it to `f`, returning the result.
 
-        // Use an unreachable branch to avoid the conditional, since we
-        // know the option is `None`
-        match *option {
-            // Safety: an option from a `Vacant` is always `None`
-            Some(_) => unsafe { unreachable_unchecked() },
+    # Example
 
-            None => {
-                // Safety: `option` is, of course, safe to write to, it's
-                // a regular mutable reference. This will leak the contents
-                // of `option`, but we know that's just a `None`, so there's
-                // no problem there. We can't use a regular assignment here
-                // because of `const` limitations.
-                unsafe { core::ptr::write(option, Some(item)) };
-
-                // Safety: `option` is now guaranteed to be `Some`, since
-                // we just wrote to it.
-                unsafe { Occupied::new_unchecked(option) }
-            }
-        }
-    }
-}
+    ```
+    use occupied::OptionExt as _;
 
-/**
-Wrapper around a mutable reference to an option, containing information about
-whether the option is vacant or occupied.
-*/
-#[derive(Debug)]
-pub enum Entry<'a, T> {
-    /// The option is occupied
-    Occupied(Occupied<'a, T>),
+    let mut pending = Some(vec![1, 2, 3]);
 
-    /// The option is vacant
-    Vacant(Vacant<'a, T>),
-}
+    let total: i32 = pending
+        .peek_some()
+        .unwrap()
+        .consume_with(|items| items.into_iter().sum());
 
-impl<'a, T> Entry<'a, T> {
-    /// Modify the item in the option, if any.
+    assert_eq!(total, 6);
+    assert_eq!(pending, None);
+    ```
+    */
     #[inline]
-    pub fn and_modify(mut self, f: impl FnOnce(&mut T)) -> Self {
-        if let Entry::Occupied(ref mut occupied) = self {
-            f(occupied.as_mut())
-        }
-
-        self
+    pub fn consume_with<R>(self, f: impl FnOnce(T) -> R) -> R {
+        f(self.take())
     }
 
     /**
-    Insert an item into the option if it isn't already occupied, and then return
-    an [`Occupied`] reference to the now-occupied option.
-     */
-    #[inline(always)]
+    Transform the item in the option by value with `f`, putting the result
+    back into the option, and return an [`Occupied`] reference to it.
+
+    # Panics
+
+    If `f` panics, the panic is propagated and the option is left vacant,
+    since the original item was moved into `f`. Unlike with `&mut T`
+    based `replace_with` helpers, this doesn't require aborting or a
+    placeholder value: [`None`] is always a valid state for the option, and
+    this [`Occupied`] reference was consumed, so nothing can observe the
+    vacancy as a broken guarantee.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    enum State {
+        Idle,
+        Running(u32),
+    }
+
+    let mut state = Some(State::Idle);
+
+    let occupied = state.peek_some().unwrap().map_in_place(|state| match state {
+        State::Idle => State::Running(1),
+        State::Running(n) => State::Running(n + 1),
+    });
+
+    assert!(matches!(occupied.get(), State::Running(1)));
+    ```
+    */
+    #[inline]
+    pub fn map_in_place(self, f: impl FnOnce(T) -> T) -> Self {
+        let (vacant, item) = self.extract();
+        vacant.insert(f(item))
+    }
+
+    /**
+    Transform the item in the option by value with the fallible function
+    `f`. If it succeeds, the result is put back into the option and an
+    [`Occupied`] reference to it is returned. If it fails, the option is left
+    vacant, and the error is returned along with a [`Vacant`] reference, so
+    that the caller can decide how to repopulate it.
+
+    As with [`map_in_place`][Self::map_in_place], if `f` panics, the option
+    is left vacant.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut input = Some(String::from("12"));
+
+    let occupied = input
+        .peek_some()
+        .unwrap()
+        .try_map_in_place(|s| s.parse::<u32>().map(|n| (n * 2).to_string()))
+        .unwrap();
+    assert_eq!(occupied.get(), "24");
+
+    let (vacant, _err) = input
+        .peek_some()
+        .unwrap()
+        .try_map_in_place(|s| s.parse::<bool>().map(|b| (!b).to_string()))
+        .unwrap_err();
+    vacant.insert(String::from("0"));
+
+    assert_eq!(input.as_deref(), Some("0"));
+    ```
+    */
+    #[inline]
+    pub fn try_map_in_place<E>(
+        self,
+        f: impl FnOnce(T) -> Result<T, E>,
+    ) -> Result<Self, (Vacant<'a, T>, E)> {
+        let (vacant, item) = self.extract();
+
+        match f(item) {
+            Ok(item) => Ok(vacant.insert(item)),
+            Err(err) => Err((vacant, err)),
+        }
+    }
+
+    /**
+    Call `f` with a shared reference to the item, and return its result.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(String::from("hello"));
+    let occupied = opt.peek_some().unwrap();
+
+    assert_eq!(occupied.with_ref(String::len), 5);
+    ```
+    */
+    #[inline]
+    pub fn with_ref<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(self.get())
+    }
+
+    /**
+    Call `f` with a mutable reference to the item, and return its result.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(vec![1, 2]);
+    let mut occupied = opt.peek_some().unwrap();
+
+    let len = occupied.with_mut(|items| {
+        items.push(3);
+        items.len()
+    });
+
+    assert_eq!(len, 3);
+    assert_eq!(occupied.take(), [1, 2, 3]);
+    ```
+    */
+    #[inline]
+    pub fn with_mut<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(self.get_mut())
+    }
+
+    /**
+    Replace the item in the option with `value`, returning the old item. The
+    option is never vacated, so this [`Occupied`] reference remains valid.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(1);
+    let mut occupied = opt.peek_some().unwrap();
+
+    assert_eq!(occupied.replace(2), 1);
+    assert_eq!(*occupied.get(), 2);
+    ```
+    */
+    #[inline]
+    pub const fn replace(&mut self, value: T) -> T {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /**
+    Swap the item in the option with `other`. The option is never vacated,
+    so this [`Occupied`] reference remains valid.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut front = Some(vec![1, 2, 3]);
+    let mut back = Vec::new();
+
+    let mut occupied = front.peek_some().unwrap();
+    occupied.swap_value(&mut back);
+
+    assert_eq!(back, [1, 2, 3]);
+    assert_eq!(front, Some(Vec::new()));
+    ```
+    */
+    #[inline]
+    pub const fn swap_value(&mut self, other: &mut T) {
+        core::mem::swap(self.get_mut(), other);
+    }
+
+    /**
+    Lend out a reborrowed [`Occupied`] reference with a shorter lifetime to
+    `f`, which may consume it (for instance, by passing it to a helper
+    function that takes it by value). Afterwards, return the result of `f`
+    along with an [`Entry`] for the option with the original lifetime,
+    since `f` may have removed the item.
+
+    A plain `&mut self` reborrow can't be offered, because consuming the
+    reborrowed proof could vacate the option out from under `self`.
+
+    # Example
+
+    ```
+    use occupied::{Entry, Occupied, OptionExt as _};
+
+    fn bump(mut occupied: Occupied<'_, i32>) -> i32 {
+        *occupied.get_mut() += 1;
+        *occupied.get()
+    }
+
+    let mut opt = Some(1);
+    let occupied = opt.peek_some().unwrap();
+
+    let (value, entry) = occupied.reborrow_with(bump);
+    assert_eq!(value, 2);
+
+    let Entry::Occupied(occupied) = entry else { unreachable!() };
+    let (_, entry) = occupied.reborrow_with(|occupied| occupied.take());
+    assert!(matches!(entry, Entry::Vacant(_)));
+    ```
+    */
+    #[inline]
+    pub fn reborrow_with<R>(self, f: impl FnOnce(Occupied<'_, T>) -> R) -> (R, Entry<'a, T>) {
+        let option = self.into_inner();
+
+        // Safety: the option in `Occupied` is guaranteed to be `Some`
+        let result = f(new_unchecked!(Occupied, &mut *option));
+        (result, examine(option))
+    }
+}
+
+impl<'a, T: Clone> Occupied<'a, T> {
+    /**
+    Clone the item in this option, leaving the option occupied. This is like
+    [`Option::cloned`], without needing to go through a reference.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(String::from("hello"));
+    let occupied = opt.peek_some().unwrap();
+
+    let copy: String = occupied.cloned();
+    assert_eq!(copy, "hello");
+    assert_eq!(opt.as_deref(), Some("hello"));
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub fn cloned(&self) -> T {
+        self.get().clone()
+    }
+
+    /**
+    Clone the item in this option into a [`Vacant`] option, returning
+    [`Occupied`] references to both.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut source = Some(String::from("hello"));
+    let mut dest = None;
+
+    let (source_ref, dest_ref) = source
+        .peek_some()
+        .unwrap()
+        .clone_into(dest.peek_empty().unwrap());
+
+    assert_eq!(source_ref.get(), "hello");
+    assert_eq!(dest_ref.get(), "hello");
+    assert_eq!(dest, Some(String::from("hello")));
+    ```
+    */
+    #[inline]
+    pub fn clone_into<'b>(self, dest: Vacant<'b, T>) -> (Occupied<'a, T>, Occupied<'b, T>) {
+        let dest = dest.insert(self.get().clone());
+        (self, dest)
+    }
+}
+
+impl<T: Copy> Occupied<'_, T> {
+    /**
+    Copy the item out of this option, leaving the option occupied. This is
+    like [`Option::copied`], without needing to go through a reference.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(5);
+    let occupied = opt.peek_some().unwrap();
+
+    assert_eq!(occupied.copied(), 5);
+    assert_eq!(occupied.take(), 5);
+    ```
+    */
+    #[inline(always)]
+    #[must_use]
+    pub const fn copied(&self) -> T {
+        *self.get()
+    }
+}
+
+impl<T: Default> Occupied<'_, T> {
+    /**
+    Take the item out of this option, replacing it with
+    [`T::default()`][Default] rather than vacating the option, so that the
+    [`Occupied`] reference remains valid. This is [`mem::take`][core::mem::take] for
+    slots.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut buffer = Some(String::from("hello"));
+    let mut occupied = buffer.peek_some().unwrap();
+
+    assert_eq!(occupied.take_default(), "hello");
+    occupied.get_mut().push_str("world");
+
+    assert_eq!(buffer.as_deref(), Some("world"));
+    ```
+    */
+    #[inline]
+    pub fn take_default(&mut self) -> T {
+        core::mem::take(self.get_mut())
+    }
+}
+
+impl<'a, 'b: 'a, T: ?Sized> Occupied<'a, &'b mut T> {
+    /**
+    For an option holding a mutable reference, get that reference with the
+    lifetime of this [`Occupied`] reference, rather than a reference to a
+    reference.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut target = String::from("hello");
+    let mut slot = Some(&mut target);
+
+    let reference: &mut String = slot.peek_some().unwrap().flatten_mut();
+    reference.push_str(", world");
+
+    assert_eq!(target, "hello, world");
+    ```
+    */
+    #[inline(always)]
+    #[must_use]
+    pub const fn flatten_mut(self) -> &'a mut T {
+        self.into_mut()
+    }
+}
+
+impl<T: Deref> Occupied<'_, T> {
+    /**
+    Get a reference to the target of the item in the option, like
+    [`Option::as_deref`]; for instance, a `&str` from an option of `String`.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(String::from("hello"));
+    let occupied = opt.peek_some().unwrap();
+
+    let s: &str = occupied.as_deref();
+    assert_eq!(s, "hello");
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub fn as_deref(&self) -> &T::Target {
+        self.get()
+    }
+}
+
+impl<T: DerefMut> Occupied<'_, T> {
+    /**
+    Get a mutable reference to the target of the item in the option, like
+    [`Option::as_deref_mut`]; for instance, a `&mut [U]` from an option of
+    `Vec<U>`.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(vec![3, 1, 2]);
+    let mut occupied = opt.peek_some().unwrap();
+
+    let items: &mut [i32] = occupied.as_deref_mut();
+    items.sort();
+
+    assert_eq!(opt, Some(vec![1, 2, 3]));
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub fn as_deref_mut(&mut self) -> &mut T::Target {
+        self.get_mut()
+    }
+}
+
+impl<T> AsRef<T> for Occupied<'_, T> {
+    fn as_ref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T> AsMut<T> for Occupied<'_, T> {
+    fn as_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+/**
+Equivalent to [`Occupied::into_inner`]. There's no corresponding conversion
+into `&mut T`, because the orphan rules forbid it; use
+[`into_mut`][Occupied::into_mut] instead.
+
+# Example
+
+```
+use occupied::OptionExt as _;
+
+fn clear<'a, T>(option: impl Into<&'a mut Option<T>>)
+where
+    T: 'a,
+{
+    *option.into() = None;
+}
+
+let mut opt = Some(1);
+clear(opt.peek_some().unwrap());
+assert_eq!(opt, None);
+```
+*/
+impl<'a, T> From<Occupied<'a, T>> for &'a mut Option<T> {
+    #[inline]
+    fn from(occupied: Occupied<'a, T>) -> Self {
+        occupied.into_inner()
+    }
+}
+
+/// Formats the item in the option, wrapped as `Occupied(..)`.
+impl<T: fmt::Debug> fmt::Debug for Occupied<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Occupied").field(self.get()).finish()
+    }
+}
+
+/**
+Formats the item in the option.
+
+# Example
+
+```
+use occupied::OptionExt as _;
+
+let mut opt = Some(10);
+let occupied = opt.peek_some().unwrap();
+
+assert_eq!(format!("{occupied} / {occupied:?}"), "10 / Occupied(10)");
+```
+*/
+impl<T: fmt::Display> fmt::Display for Occupied<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.get(), f)
+    }
+}
+
+/// Implement the comparison and hashing traits for an occupied proof type,
+/// delegating to the item in the option. Proofs can be compared with each
+/// other, or with a plain item.
+macro_rules! forward_comparisons {
+    ($Type:ident) => {
+        impl<T: PartialEq> PartialEq for $Type<'_, T> {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.get() == other.get()
+            }
+        }
+
+        impl<T: Eq> Eq for $Type<'_, T> {}
+
+        impl<T: PartialOrd> PartialOrd for $Type<'_, T> {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                self.get().partial_cmp(other.get())
+            }
+        }
+
+        impl<T: Ord> Ord for $Type<'_, T> {
+            #[inline]
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.get().cmp(other.get())
+            }
+        }
+
+        impl<T: PartialEq> PartialEq<T> for $Type<'_, T> {
+            #[inline]
+            fn eq(&self, other: &T) -> bool {
+                self.get() == other
+            }
+        }
+
+        impl<T: PartialOrd> PartialOrd<T> for $Type<'_, T> {
+            #[inline]
+            fn partial_cmp(&self, other: &T) -> Option<core::cmp::Ordering> {
+                self.get().partial_cmp(other)
+            }
+        }
+
+        impl<T: core::hash::Hash> core::hash::Hash for $Type<'_, T> {
+            #[inline]
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.get().hash(state)
+            }
+        }
+    };
+}
+
+forward_comparisons!(Occupied);
+
+/**
+Iterates over a mutable reference to the item in the option, which is always
+exactly one item. This mirrors [`&mut Option<T>`][Option], and leaves the
+option occupied.
+
+# Example
+
+```
+use occupied::OptionExt as _;
+
+let mut opt = Some(1);
+
+for item in opt.peek_some().unwrap() {
+    *item += 1;
+}
+
+assert_eq!(opt, Some(2));
+```
+*/
+impl<'a, T> IntoIterator for Occupied<'a, T> {
+    type Item = &'a mut T;
+    type IntoIter = core::option::IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_inner().iter_mut()
+    }
+}
+
+/**
+Dereferences to the item in the option, so that its methods can be called
+directly on the [`Occupied`] reference.
+
+# Example
+
+```
+use occupied::OptionExt as _;
+
+let mut opt = Some(String::from("hello"));
+let mut occupied = opt.peek_some().unwrap();
+
+occupied.push_str(", world");
+assert_eq!(occupied.len(), 12);
+assert_eq!(opt.as_deref(), Some("hello, world"));
+```
+*/
+impl<T> Deref for Occupied<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T> DerefMut for Occupied<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<'a, T> OccupiedRef<'a, T> {
+    /**
+    Try to create a new [`OccupiedRef`] instance, referencing an [`Option`]
+    that is definitely [`Some`]. Returns [`None`] if the option is [`None`].
+
+    # Example
+
+    ```
+    use occupied::OccupiedRef;
+
+    fn describe(name: OccupiedRef<'_, String>) -> String {
+        format!("hello, {}", name.get())
+    }
+
+    let name = Some(String::from("world"));
+    let witness = OccupiedRef::new(&name).unwrap();
+
+    // `OccupiedRef` is `Copy`, so it can be used more than once
+    assert_eq!(describe(witness), "hello, world");
+    assert_eq!(witness.into_ref(), "world");
+
+    assert!(OccupiedRef::new(&None::<String>).is_none());
+    ```
+    */
+    #[inline(always)]
+    #[must_use]
+    pub const fn new(option: &'a Option<T>) -> Option<Self> {
+        match option {
+            Some(_) => Some(new_unchecked!(OccupiedRef, option)),
+            None => None,
+        }
+    }
+}
+
+impl<T> Clone for OccupiedRef<'_, T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for OccupiedRef<'_, T> {}
+
+impl<T> AsRef<T> for OccupiedRef<'_, T> {
+    fn as_ref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OccupiedRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OccupiedRef").field(self.get()).finish()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for OccupiedRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.get(), f)
+    }
+}
+
+forward_comparisons!(OccupiedRef);
+
+impl<'a, T> IntoIterator for OccupiedRef<'a, T> {
+    type Item = &'a T;
+    type IntoIter = core::option::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_inner().iter()
+    }
+}
+
+impl<'a, T> From<Occupied<'a, T>> for OccupiedRef<'a, T> {
+    #[inline]
+    fn from(occupied: Occupied<'a, T>) -> Self {
+        occupied.downgrade()
+    }
+}
+
+impl<'a, T> Vacant<'a, T> {
+    /**
+    Try to create a new [`Vacant`] instance, referencing an [`Option`] that is
+    definitely [`None`]. Returns [`None`] if the option is [`Some`].
+     */
+    #[inline(always)]
+    #[must_use]
+    pub const fn new(option: &'a mut Option<T>) -> Option<Self> {
+        match examine(option) {
+            Entry::Vacant(vacant) => Some(vacant),
+            Entry::Occupied(_) => None,
+        }
+    }
+
+    /**
+    Insert an item into the [`Vacant`] option, and return an [`Occupied`]
+    reference to the inserted item. This will be *slightly* more efficient
+    than [`Option::insert`], since [`Option::insert`] must check whether
+    the option is currently [`Some`] and destruct if so.
+    */
+    #[inline(always)]
+    pub const fn insert(self, item: T) -> Occupied<'a, T> {
+        let option = self.into_inner();
+        debug_assert!(option.is_none());
+
+        // Use an unreachable branch to avoid the conditional, since we
+        // know the option is `None`
+        #[cfg(not(feature = "forbid-unsafe"))]
+        match *option {
+            // Safety: an option from a `Vacant` is always `None`
+            Some(_) => unsafe { unreachable_unchecked() },
+
+            // Safety: `option` is, of course, safe to write to, it's
+            // a regular mutable reference. This will leak the contents
+            // of `option`, but we know that's just a `None`, so there's
+            // no problem there. We can't use a regular assignment here
+            // because of `const` limitations.
+            None => unsafe { core::ptr::write(option, Some(item)) },
+        }
+
+        // Without `unsafe`, the closest we can get is to swap the item in
+        // and forget the `None` that comes out.
+        #[cfg(feature = "forbid-unsafe")]
+        core::mem::forget(option.replace(item));
+
+        // Safety: `option` is now guaranteed to be `Some`, since we just
+        // wrote to it.
+        new_unchecked!(Occupied, option)
+    }
+
+    /**
+    Insert the item returned by `f` into the [`Vacant`] option, and return an
+    [`Occupied`] reference to it. `f` is only called when it's needed, which
+    is useful if the item is expensive to construct.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut cache = None;
+
+    if let Some(vacant) = cache.peek_empty() {
+        let occupied = vacant.insert_with(|| "expensive".repeat(3));
+        assert_eq!(occupied.len(), 27);
+    }
+    ```
+    */
+    #[inline]
+    pub fn insert_with(self, f: impl FnOnce() -> T) -> Occupied<'a, T> {
+        self.insert(f())
+    }
+
+    /**
+    Wrap this back up into an [`Entry`], without re-examining the option.
+
+    # Example
+
+    ```
+    use occupied::{Entry, OptionExt as _};
+
+    // Return whatever state the option is in after maybe filling it
+    fn maybe_fill(option: &mut Option<i32>, fill: bool) -> Entry<'_, i32> {
+        match option.entry() {
+            Entry::Vacant(vacant) if fill => vacant.insert(0).into_entry(),
+            Entry::Vacant(vacant) => vacant.into_entry(),
+            Entry::Occupied(occupied) => occupied.into_entry(),
+        }
+    }
+
+    let mut opt = None;
+    assert!(matches!(maybe_fill(&mut opt, false), Entry::Vacant(_)));
+    assert!(matches!(maybe_fill(&mut opt, true), Entry::Occupied(_)));
+    ```
+    */
+    #[inline(always)]
+    #[must_use]
+    pub const fn into_entry(self) -> Entry<'a, T> {
+        Entry::Vacant(self)
+    }
+
+    /**
+    Lend out a reborrowed [`Vacant`] reference with a shorter lifetime to
+    `f`, which may consume it. Afterwards, return the result of `f` along
+    with an [`Entry`] for the option with the original lifetime, since `f`
+    may have inserted an item.
+
+    As with [`Occupied::reborrow_with`], a plain `&mut self` reborrow can't
+    be offered, because inserting through the reborrowed proof would fill
+    the option out from under `self`.
+
+    # Example
+
+    ```
+    use occupied::{Entry, OptionExt as _, Vacant};
+
+    // A helper which may decline to insert anything
+    fn fill_if_even(vacant: Vacant<'_, i32>, n: i32) -> bool {
+        match n % 2 {
+            0 => {
+                vacant.insert(n);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    let mut opt = None;
+    let vacant = opt.peek_empty().unwrap();
+
+    let (filled, entry) = vacant.reborrow_with(|vacant| fill_if_even(vacant, 3));
+    assert!(!filled);
+
+    // The helper declined, so the option is still vacant
+    let Entry::Vacant(vacant) = entry else { unreachable!() };
+    let (filled, entry) = vacant.reborrow_with(|vacant| fill_if_even(vacant, 4));
+    assert!(filled);
+    assert!(matches!(entry, Entry::Occupied(_)));
+    assert_eq!(opt, Some(4));
+    ```
+    */
+    #[inline]
+    pub fn reborrow_with<R>(self, f: impl FnOnce(Vacant<'_, T>) -> R) -> (R, Entry<'a, T>) {
+        let option = self.into_inner();
+
+        // Safety: the option in `Vacant` is guaranteed to be `None`
+        let result = f(new_unchecked!(Vacant, &mut *option));
+        (result, examine(option))
+    }
+}
+
+impl<'a, T: Default> Vacant<'a, T> {
+    /**
+    Insert [`T::default()`][Default] into the [`Vacant`] option, and return an
+    [`Occupied`] reference to it.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut counts: Option<Vec<u32>> = None;
+
+    let mut occupied = counts.peek_empty().unwrap().insert_default();
+    occupied.push(1);
+
+    assert_eq!(counts, Some(vec![1]));
+    ```
+    */
+    #[inline]
+    pub fn insert_default(self) -> Occupied<'a, T> {
+        self.insert(T::default())
+    }
+}
+
+impl<'a, T> From<Vacant<'a, T>> for &'a mut Option<T> {
+    /// Equivalent to [`Vacant::into_inner`].
+    #[inline]
+    fn from(vacant: Vacant<'a, T>) -> Self {
+        vacant.into_inner()
+    }
+}
+
+/**
+Wrapper around a mutable reference to an option, containing information about
+whether the option is vacant or occupied.
+*/
+#[derive(Debug)]
+pub enum Entry<'a, T> {
+    /// The option is occupied
+    Occupied(Occupied<'a, T>),
+
+    /// The option is vacant
+    Vacant(Vacant<'a, T>),
+}
+
+/**
+Wrap an [`Occupied`] reference back up into an [`Entry`], without
+re-examining the option.
+
+# Example
+
+```
+use occupied::{Entry, OptionExt as _};
+
+let mut opt = Some(1);
+let occupied = opt.peek_some().unwrap();
+
+let entry: Entry<'_, i32> = occupied.into();
+assert_eq!(*entry.or_insert(2).get(), 1);
+```
+*/
+impl<'a, T> From<Occupied<'a, T>> for Entry<'a, T> {
+    #[inline]
+    fn from(occupied: Occupied<'a, T>) -> Self {
+        occupied.into_entry()
+    }
+}
+
+/// Wrap a [`Vacant`] reference back up into an [`Entry`], without
+/// re-examining the option.
+impl<'a, T> From<Vacant<'a, T>> for Entry<'a, T> {
+    #[inline]
+    fn from(vacant: Vacant<'a, T>) -> Self {
+        vacant.into_entry()
+    }
+}
+
+/// The inverse of [`Entry::into_result`].
+impl<'a, T> From<Result<Occupied<'a, T>, Vacant<'a, T>>> for Entry<'a, T> {
+    #[inline]
+    fn from(result: Result<Occupied<'a, T>, Vacant<'a, T>>) -> Self {
+        match result {
+            Ok(occupied) => Entry::Occupied(occupied),
+            Err(vacant) => Entry::Vacant(vacant),
+        }
+    }
+}
+
+impl<'a, T> Entry<'a, T> {
+    /**
+    Get a slice containing the item in the option, which is empty if the
+    option is vacant. This mirrors [`Option::as_slice`].
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut a = Some(1);
+    let mut b: Option<i32> = None;
+
+    assert_eq!(a.entry().as_slice(), [1]);
+    assert_eq!(b.entry().as_slice(), []);
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub const fn as_slice(&self) -> &[T] {
+        match self {
+            Entry::Occupied(occupied) => occupied.as_slice(),
+            Entry::Vacant(_) => &[],
+        }
+    }
+
+    /**
+    Get a mutable slice containing the item in the option, which is empty if
+    the option is vacant. This mirrors [`Option::as_mut_slice`].
+    */
+    #[inline]
+    #[must_use]
+    pub const fn as_mut_slice(&mut self) -> &mut [T] {
+        match self {
+            Entry::Occupied(occupied) => occupied.as_mut_slice(),
+            Entry::Vacant(_) => &mut [],
+        }
+    }
+
+    /**
+    Convert this into a [`Result`], which is [`Ok`] if the option is
+    occupied and [`Err`] if it's vacant, so that it can be used with `?`.
+
+    # Example
+
+    ```
+    use occupied::{Occupied, OptionExt as _, Vacant};
+
+    fn first_char(option: &mut Option<String>) -> Result<char, Vacant<'_, String>> {
+        let occupied: Occupied<'_, String> = option.entry().into_result()?;
+        Ok(occupied.chars().next().unwrap_or(' '))
+    }
+
+    let mut name = None;
+    first_char(&mut name).unwrap_err().insert(String::from("ferris"));
+    assert_eq!(first_char(&mut name).ok(), Some('f'));
+    ```
+    */
+    #[inline]
+    pub const fn into_result(self) -> Result<Occupied<'a, T>, Vacant<'a, T>> {
+        match self {
+            Entry::Occupied(occupied) => Ok(occupied),
+            Entry::Vacant(vacant) => Err(vacant),
+        }
+    }
+
+    /// Returns true if the option is occupied.
+    #[inline]
+    #[must_use]
+    pub const fn is_occupied(&self) -> bool {
+        matches!(self, Entry::Occupied(_))
+    }
+
+    /// Returns true if the option is vacant.
+    #[inline]
+    #[must_use]
+    pub const fn is_vacant(&self) -> bool {
+        matches!(self, Entry::Vacant(_))
+    }
+
+    /**
+    Get a reference to the item in the option, if any, without consuming
+    the entry.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(1);
+    let mut entry = opt.entry();
+
+    assert!(entry.is_occupied());
+    assert_eq!(entry.get(), Some(&1));
+
+    if let Some(item) = entry.get_mut() {
+        *item += 1;
+    }
+
+    assert_eq!(*entry.or_insert(10).get(), 2);
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub const fn get(&self) -> Option<&T> {
+        match self {
+            Entry::Occupied(occupied) => Some(occupied.get()),
+            Entry::Vacant(_) => None,
+        }
+    }
+
+    /// Get a mutable reference to the item in the option, if any, without
+    /// consuming the entry.
+    #[inline]
+    #[must_use]
+    pub const fn get_mut(&mut self) -> Option<&mut T> {
+        match self {
+            Entry::Occupied(occupied) => Some(occupied.get_mut()),
+            Entry::Vacant(_) => None,
+        }
+    }
+
+    /// Modify the item in the option, if any.
+    #[inline]
+    pub fn and_modify(mut self, f: impl FnOnce(&mut T)) -> Self {
+        if let Entry::Occupied(ref mut occupied) = self {
+            f(occupied.as_mut())
+        }
+
+        self
+    }
+
+    /**
+    Modify the item in the option, if any, through its [`Occupied`]
+    reference, so that proof-level operations like
+    [`replace`][Occupied::replace] are available. The option is still
+    occupied afterwards, since the reference can't be consumed.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(String::from("old"));
+    let mut previous = None;
+
+    let occupied = opt
+        .entry()
+        .and_modify_occupied(|occupied| previous = Some(occupied.replace(String::from("new"))))
+        .or_insert_with(String::new);
+
+    assert_eq!(occupied.get(), "new");
+    assert_eq!(previous.as_deref(), Some("old"));
+    ```
+    */
+    #[inline]
+    pub fn and_modify_occupied(mut self, f: impl FnOnce(&mut Occupied<'a, T>)) -> Self {
+        if let Entry::Occupied(ref mut occupied) = self {
+            f(occupied)
+        }
+
+        self
+    }
+
+    /**
+    If the option is occupied, modify its item with `modify`; otherwise,
+    insert an item produced by `insert`. Either way, return an [`Occupied`]
+    reference to the now-occupied option. This is equivalent to
+    `.and_modify(modify).or_insert_with(insert)`, but only checks the
+    entry once.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut count = None;
+
+    for _ in 0..3 {
+        count.entry().and_modify_or_insert(|count| *count += 1, || 1);
+    }
+
+    assert_eq!(count, Some(3));
+    ```
+    */
+    #[inline]
+    pub fn and_modify_or_insert(
+        self,
+        modify: impl FnOnce(&mut T),
+        insert: impl FnOnce() -> T,
+    ) -> Occupied<'a, T> {
+        match self {
+            Entry::Occupied(mut occupied) => {
+                modify(occupied.get_mut());
+                occupied
+            }
+            Entry::Vacant(vacant) => vacant.insert(insert()),
+        }
+    }
+
+    /**
+    Insert an item into the option if it isn't already occupied, and then return
+    an [`Occupied`] reference to the now-occupied option.
+     */
+    #[inline(always)]
     pub fn or_insert(self, default: T) -> Occupied<'a, T> {
         self.or_insert_with(|| default)
     }
 
     /**
-    Insert an item into the option if it isn't already occupied, using a
-    function to produce the item, then return an [`Occupied`] reference to the
-    now-occupied option.
+    Insert an item into the option if it isn't already occupied, using a
+    function to produce the item, then return an [`Occupied`] reference to the
+    now-occupied option.
+     */
+    #[inline]
+    pub fn or_insert_with(self, default: impl FnOnce() -> T) -> Occupied<'a, T> {
+        match self {
+            Entry::Occupied(occupied) => occupied,
+            Entry::Vacant(vacant) => vacant.insert(default()),
+        }
+    }
+
+    /**
+    Get an [`Occupied`] reference to the item in the option, or try to
+    create one with `f` if it's vacant. If `f` fails, the option is left
+    vacant, and the error is returned.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut port: Option<u16> = None;
+
+    let err = port.entry().or_try_insert_with(|| "http".parse()).unwrap_err();
+    assert_eq!(err.to_string(), "invalid digit found in string");
+    assert_eq!(port, None);
+
+    let occupied = port.entry().or_try_insert_with(|| "8080".parse()).unwrap();
+    assert_eq!(*occupied.get(), 8080);
+    ```
+    */
+    #[inline]
+    pub fn or_try_insert_with<E>(
+        self,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<Occupied<'a, T>, E> {
+        match self {
+            Entry::Occupied(occupied) => Ok(occupied),
+            Entry::Vacant(vacant) => f().map(|item| vacant.insert(item)),
+        }
+    }
+
+    /**
+    If the option is vacant, try to produce an item for it with a fallible
+    function, and insert it if there is one. Returns the updated [`Entry`],
+    which is still vacant if `fallback` returned [`None`].
+
+    # Example
+
+    ```
+    use occupied::{Entry, OptionExt as _};
+
+    let mut local: Option<i32> = None;
+
+    // The first fallback misses, but the second one hits
+    let entry = local.entry().or_else_entry(|| None).or_else_entry(|| Some(3));
+    assert!(matches!(entry, Entry::Occupied(_)));
+    assert_eq!(local, Some(3));
+    ```
+    */
+    #[inline]
+    pub fn or_else_entry(self, fallback: impl FnOnce() -> Option<T>) -> Self {
+        match self {
+            Entry::Occupied(occupied) => Entry::Occupied(occupied),
+            Entry::Vacant(vacant) => match fallback() {
+                Some(item) => Entry::Occupied(vacant.insert(item)),
+                None => Entry::Vacant(vacant),
+            },
+        }
+    }
+
+    /**
+    Insert an item into the option if it isn't already occupied, using a
+    function pointer and a context parameter to produce the item, then return
+    an [`Occupied`] reference to the now-occupied option.
+
+    This is equivalent to [`.or_insert_with()`][Self::or_insert_with], but
+    because it takes a plain function pointer rather than a closure, it's
+    only monomorphized once per `T` and `C`, rather than once per call site.
+    This can matter for code size in constrained environments.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    fn next_id(counter: &mut u32) -> u32 {
+        *counter += 1;
+        *counter
+    }
+
+    let mut counter = 0;
+    let mut a = None;
+    let mut b = Some(10);
+
+    assert_eq!(*a.entry().or_insert_with_ctx(&mut counter, next_id).get(), 1);
+    assert_eq!(*b.entry().or_insert_with_ctx(&mut counter, next_id).get(), 10);
+    assert_eq!(counter, 1);
+    ```
+     */
+    #[inline]
+    pub fn or_insert_with_ctx<C: ?Sized>(self, ctx: &mut C, f: fn(&mut C) -> T) -> Occupied<'a, T> {
+        match self {
+            Entry::Occupied(occupied) => occupied,
+            Entry::Vacant(vacant) => vacant.insert(f(ctx)),
+        }
+    }
+
+    /**
+    Insert an item into the option if it isn't already occupied, by passing
+    the [`Vacant`] reference to a function which is responsible for
+    performing the insertion, then return an [`Occupied`] reference to the
+    now-occupied option. This allows the insertion to use any strategy that
+    requires a [`Vacant`], rather than just producing a `T` by value.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = None;
+    let occupied = opt.entry().or_insert_via(|vacant| vacant.insert(5));
+    assert_eq!(*occupied.get(), 5);
+
+    let occupied = opt.entry().or_insert_via(|_| unreachable!());
+    assert_eq!(*occupied.get(), 5);
+    ```
      */
     #[inline]
-    pub fn or_insert_with(self, default: impl FnOnce() -> T) -> Occupied<'a, T> {
+    pub fn or_insert_via(
+        self,
+        insert: impl FnOnce(Vacant<'a, T>) -> Occupied<'a, T>,
+    ) -> Occupied<'a, T> {
         match self {
             Entry::Occupied(occupied) => occupied,
-            Entry::Vacant(vacant) => vacant.insert(default()),
+            Entry::Vacant(vacant) => insert(vacant),
         }
     }
 
@@ -423,7 +2093,115 @@ impl<'a, T> Entry<'a, T> {
         let item = opt.take();
 
         // Safety: after `take`, the `option` is guaranteed to be `None`
-        (item, unsafe { Vacant::new_unchecked(opt) })
+        (item, new_unchecked!(Vacant, opt))
+    }
+
+    /**
+    Move the item out of `source` and into this option, if this option is
+    vacant and `source` is occupied. An item already in this option is never
+    overwritten:
+
+    | `self`   | `source` | Result                                     |
+    |----------|----------|--------------------------------------------|
+    | vacant   | occupied | The item is moved; `self` becomes occupied |
+    | vacant   | vacant   | Nothing happens; `self` stays vacant       |
+    | occupied | occupied | Nothing happens; both items stay put       |
+    | occupied | vacant   | Nothing happens                            |
+
+    Returns an [`Entry`] describing the state of this option afterwards.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut staged = Some("draft");
+    let mut live = None;
+
+    let entry = live.entry().transfer_from(staged.entry());
+    assert!(entry.is_occupied());
+    assert_eq!((staged, live), (None, Some("draft")));
+
+    let mut staged = Some("second draft");
+    live.entry().transfer_from(staged.entry());
+    assert_eq!((staged, live), (Some("second draft"), Some("draft")));
+    ```
+    */
+    #[inline]
+    pub fn transfer_from(self, source: Entry<'_, T>) -> Self {
+        match (self, source) {
+            (Entry::Vacant(vacant), Entry::Occupied(source)) => {
+                Entry::Occupied(vacant.insert(source.take()))
+            }
+            (entry, _) => entry,
+        }
+    }
+
+    /**
+    Replace the whole state of this option with the result of `f`, which is
+    given the current contents by value, and return an [`Entry`] describing
+    the new state. This is the most general transition of a single option.
+    If `f` panics, the option is left vacant.
+
+    # Example
+
+    ```
+    use occupied::{Entry, OptionExt as _};
+
+    // A counter which resets itself after 3
+    fn tick(state: &mut Option<u8>) -> Entry<'_, u8> {
+        state.entry().replace_with(|state| match state {
+            None => Some(1),
+            Some(3) => None,
+            Some(n) => Some(n + 1),
+        })
+    }
+
+    let mut state = None;
+
+    assert_eq!(tick(&mut state).get(), Some(&1));
+    assert_eq!(tick(&mut state).get(), Some(&2));
+    assert_eq!(tick(&mut state).get(), Some(&3));
+    assert!(tick(&mut state).is_vacant());
+    ```
+    */
+    #[inline]
+    pub fn replace_with(self, f: impl FnOnce(Option<T>) -> Option<T>) -> Self {
+        let opt = self.into_inner();
+        *opt = f(opt.take());
+        examine(opt)
+    }
+
+    /**
+    Insert an item into this option, unconditionally, and return both the
+    item that was previously there, if any, and an [`Occupied`] reference to
+    the now-occupied option. This mirrors [`Option::replace`].
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(1);
+
+    let (previous, occupied) = opt.entry().insert(2);
+    assert_eq!(previous, Some(1));
+    assert_eq!(*occupied.get(), 2);
+
+    let mut opt = None;
+
+    let (previous, occupied) = opt.entry().insert(3);
+    assert_eq!(previous, None);
+    assert_eq!(*occupied.get(), 3);
+    ```
+    */
+    #[inline]
+    pub const fn insert(self, item: T) -> (Option<T>, Occupied<'a, T>) {
+        let opt = self.into_inner();
+        let previous = opt.replace(item);
+
+        // Safety: after `replace`, the `option` is guaranteed to be `Some`
+        (previous, new_unchecked!(Occupied, opt))
     }
 
     /**
@@ -437,6 +2215,143 @@ impl<'a, T> Entry<'a, T> {
             Entry::Vacant(vacant) => vacant.into_inner(),
         }
     }
+
+    /**
+    Lend out a reborrowed [`Entry`] with a shorter lifetime to `f`, which
+    may consume it. Afterwards, return the result of `f` along with a fresh
+    [`Entry`] for the option with the original lifetime, reflecting any
+    insertion or removal done by `f`.
+    */
+    #[inline]
+    pub fn reborrow_with<R>(self, f: impl FnOnce(Entry<'_, T>) -> R) -> (R, Entry<'a, T>) {
+        let option = self.into_inner();
+        let result = f(examine(&mut *option));
+        (result, examine(option))
+    }
+
+    /// Get a raw pointer to the referenced [`Option`], for identity
+    /// comparisons.
+    #[inline]
+    #[must_use]
+    pub const fn as_ptr(&self) -> *const Option<T> {
+        match self {
+            Entry::Occupied(occupied) => occupied.as_ptr(),
+            Entry::Vacant(vacant) => vacant.as_ptr(),
+        }
+    }
+
+    /**
+    Returns true if this [`Entry`] refers to the option at `option`, by
+    pointer identity.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    struct Config {
+        name: Option<&'static str>,
+        path: Option<&'static str>,
+    }
+
+    let mut config = Config { name: None, path: Some("/") };
+
+    // Take the field addresses before borrowing a field mutably
+    let name = core::ptr::from_ref(&config.name);
+    let path = core::ptr::from_ref(&config.path);
+
+    let entry = config.path.entry();
+    assert!(entry.refers_to(path));
+    assert!(!entry.refers_to(name));
+    assert_eq!(entry.as_ptr(), path);
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub fn refers_to(&self, option: *const Option<T>) -> bool {
+        core::ptr::eq(self.as_ptr(), option)
+    }
+}
+
+impl<'a, T: Clone> Entry<'a, T> {
+    /**
+    Insert a clone of `template` into the option if it isn't already
+    occupied, then return an [`Occupied`] reference to the now-occupied
+    option. `template` is only cloned if it's actually inserted.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let template = String::from("default");
+    let mut a = None;
+    let mut b = Some(String::from("custom"));
+
+    assert_eq!(a.entry().or_insert_cloned(&template).get(), "default");
+    assert_eq!(b.entry().or_insert_cloned(&template).get(), "custom");
+    ```
+    */
+    #[inline]
+    pub fn or_insert_cloned(self, template: &T) -> Occupied<'a, T> {
+        self.or_insert_with(|| template.clone())
+    }
+}
+
+impl<'a, T: Default> Entry<'a, T> {
+    /**
+    Get an [`Occupied`] reference to the item in the option, inserting
+    [`T::default()`][Default] first if it's vacant. This mirrors the
+    `or_default` method on `HashMap`'s entries.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut total: Option<u32> = None;
+
+    for n in [1, 2, 3] {
+        *total.entry().or_default().get_mut() += n;
+    }
+
+    assert_eq!(total, Some(6));
+    ```
+    */
+    #[inline]
+    pub fn or_default(self) -> Occupied<'a, T> {
+        match self {
+            Entry::Occupied(occupied) => occupied,
+            Entry::Vacant(vacant) => vacant.insert_default(),
+        }
+    }
+}
+
+/**
+Iterates over a mutable reference to the item in the option, if any, like
+[`&mut Option<T>`][Option].
+
+# Example
+
+```
+use occupied::OptionExt as _;
+
+let mut a = Some(1);
+let mut b = None;
+let mut c = Some(3);
+
+let items: Vec<&mut i32> = a.entry().into_iter().chain(b.entry()).chain(c.entry()).collect();
+assert_eq!(items, [&mut 1, &mut 3]);
+```
+*/
+impl<'a, T> IntoIterator for Entry<'a, T> {
+    type Item = &'a mut T;
+    type IntoIter = core::option::IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_inner().iter_mut()
+    }
 }
 
 /**
@@ -448,8 +2363,8 @@ instead of this.
 #[inline]
 pub const fn examine<T>(option: &mut Option<T>) -> Entry<'_, T> {
     match option {
-        opt @ &mut Some(_) => Entry::Occupied(unsafe { Occupied::new_unchecked(opt) }),
-        opt @ &mut None => Entry::Vacant(unsafe { Vacant::new_unchecked(opt) }),
+        opt @ &mut Some(_) => Entry::Occupied(new_unchecked!(Occupied, opt)),
+        opt @ &mut None => Entry::Vacant(new_unchecked!(Vacant, opt)),
     }
 }
 
@@ -466,6 +2381,129 @@ pub trait OptionExt<T> {
     #[must_use]
     fn peek_some(&mut self) -> Option<Occupied<'_, T>>;
 
+    /**
+    Identical to [`.peek_some()`][Self::peek_some], except that it hints to
+    the optimizer that the option is expected to be [`Some`], and that the
+    vacant path is cold. Useful in hot loops where vacancy is exceptional.
+    */
+    #[inline(always)]
+    #[must_use]
+    fn peek_some_likely(&mut self) -> Option<Occupied<'_, T>> {
+        match self.entry() {
+            Entry::Occupied(occupied) => Some(occupied),
+            Entry::Vacant(_) => {
+                cold();
+                None
+            }
+        }
+    }
+
+    /**
+    Identical to [`.peek_some()`][Self::peek_some], except that it hints to
+    the optimizer that the option is expected to be [`None`], and that the
+    occupied path is cold.
+    */
+    #[inline(always)]
+    #[must_use]
+    fn peek_some_unlikely(&mut self) -> Option<Occupied<'_, T>> {
+        match self.entry() {
+            Entry::Occupied(occupied) => {
+                cold();
+                Some(occupied)
+            }
+            Entry::Vacant(_) => None,
+        }
+    }
+
+    /**
+    Try to get an [`Occupied`] reference to this option, if it's occupied
+    and the item satisfies `predicate`. Returns [`None`] otherwise.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut session = Some(String::from("expired:1234"));
+
+    if let Some(occupied) = session.peek_some_if(|token| token.starts_with("expired:")) {
+        occupied.take();
+    }
+
+    assert_eq!(session, None);
+    ```
+    */
+    #[inline]
+    #[must_use]
+    fn peek_some_if(&mut self, predicate: impl FnOnce(&T) -> bool) -> Option<Occupied<'_, T>> {
+        self.peek_some()
+            .filter(|occupied| predicate(occupied.get()))
+    }
+
+    /**
+    Get an [`Occupied`] reference to this option, or a [`VacantError`] if
+    it's vacant. This is like [`.peek_some()`][Self::peek_some], but the
+    error can be propagated with `?`, and still holds a [`Vacant`] reference
+    to the option.
+
+    # Example
+
+    ```
+    use occupied::{OptionExt as _, VacantError};
+
+    fn double(option: &mut Option<i32>) -> Result<i32, VacantError<'_, i32>> {
+        let mut occupied = option.try_peek_some()?;
+        *occupied.get_mut() *= 2;
+        Ok(*occupied.get())
+    }
+
+    let mut opt = None;
+
+    let err = double(&mut opt).unwrap_err();
+    assert_eq!(err.to_string(), "option is vacant");
+    err.into_vacant().insert(5);
+
+    assert_eq!(double(&mut opt).ok(), Some(10));
+    ```
+    */
+    #[inline]
+    fn try_peek_some(&mut self) -> Result<Occupied<'_, T>, VacantError<'_, T>> {
+        self.entry().into_result().map_err(VacantError::new)
+    }
+
+    /**
+    Try to get a shared [`OccupiedRef`] reference to this option. Returns
+    [`None`] if `self` is [`None`]. Unlike [`.peek_some()`][Self::peek_some],
+    this only requires shared access to the option. This is a provided method
+    that's available whenever `Self` can be borrowed as an [`Option`], and is
+    equivalent to [`OccupiedRef::new`].
+
+    # Example
+
+    ```
+    use occupied::{OccupiedRef, OptionExt as _};
+
+    fn longest<'a>(names: &'a [Option<String>]) -> Option<OccupiedRef<'a, String>> {
+        names
+            .iter()
+            .filter_map(|name| name.peek_some_ref())
+            .max_by_key(|name| name.get().len())
+    }
+
+    let names = [Some("Ann".to_owned()), None, Some("Beatrice".to_owned())];
+    assert_eq!(longest(&names).unwrap(), "Beatrice".to_owned());
+    assert!(longest(&[None, None]).is_none());
+    ```
+    */
+    #[inline(always)]
+    #[must_use]
+    fn peek_some_ref(&self) -> Option<OccupiedRef<'_, T>>
+    where
+        Self: Borrow<Option<T>>,
+    {
+        OccupiedRef::new(self.borrow())
+    }
+
     /**
     Try to get an [`Vacant`] reference to this option. Returns [`None`] if
     `self` is [`Some`]; otherwise returns an [`Vacant`] which can be used
@@ -474,6 +2512,39 @@ pub trait OptionExt<T> {
     #[must_use]
     fn peek_empty(&mut self) -> Option<Vacant<'_, T>>;
 
+    /**
+    Get a [`Vacant`] reference to this option, or an [`OccupiedError`] if
+    it's occupied. This is like [`.peek_empty()`][Self::peek_empty], but the
+    error can be propagated with `?`, and still holds an [`Occupied`]
+    reference to the existing item, like the error from the unstable
+    `Option::try_insert`.
+
+    # Example
+
+    ```
+    use occupied::{OccupiedError, OptionExt as _};
+
+    fn register(slot: &mut Option<u32>, id: u32) -> Result<(), OccupiedError<'_, u32>> {
+        slot.try_peek_empty()?.insert(id);
+        Ok(())
+    }
+
+    let mut slot = None;
+    register(&mut slot, 1).unwrap();
+
+    let err = register(&mut slot, 2).unwrap_err();
+    assert_eq!(*err.get(), 1);
+    assert_eq!(err.into_occupied().replace(2), 1);
+    ```
+    */
+    #[inline]
+    fn try_peek_empty(&mut self) -> Result<Vacant<'_, T>, OccupiedError<'_, T>> {
+        match self.entry() {
+            Entry::Occupied(occupied) => Err(OccupiedError::new(occupied)),
+            Entry::Vacant(vacant) => Ok(vacant),
+        }
+    }
+
     /**
     Get an entry for this option, allowing in-place manipulation, insertion,
     or removal of the contained value.
@@ -487,6 +2558,82 @@ pub trait OptionExt<T> {
      */
     fn emplace(&mut self, item: T) -> Occupied<'_, T>;
 
+    /**
+    Insert an item into this option, and return both the item that was
+    previously there, if any, and an [`Occupied`] reference to the
+    now-occupied [`Option`]. This is like [`Option::replace`], without
+    needing to check the option again afterwards.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut current = Some("old");
+
+    let (previous, occupied) = current.replace_entry("new");
+    assert_eq!(previous, Some("old"));
+    assert_eq!(*occupied.get(), "new");
+    ```
+    */
+    #[inline(always)]
+    fn replace_entry(&mut self, item: T) -> (Option<T>, Occupied<'_, T>) {
+        self.entry().insert(item)
+    }
+
+    /**
+    Call `item` to get an item to insert into this option, replacing any
+    existing item, then return an [`Occupied`] reference to the
+    now-occupied [`Option`]. This is the lazy version of
+    [`emplace`][Self::emplace].
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut buffer = Some(vec![1, 2, 3]);
+
+    let occupied = buffer.emplace_with(|| Vec::with_capacity(1024));
+    assert!(occupied.is_empty());
+    assert!(occupied.capacity() >= 1024);
+    ```
+    */
+    #[inline(always)]
+    fn emplace_with(&mut self, item: impl FnOnce() -> T) -> Occupied<'_, T> {
+        self.emplace(item())
+    }
+
+    /**
+    Insert an item into this option, but only if it's vacant, and return an
+    [`Occupied`] reference to it. If the option is already occupied, the
+    existing item is left alone, and the rejected item is returned along
+    with an [`Occupied`] reference to the existing one. This is like the
+    unstable `Option::try_insert`.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    // First writer wins
+    let mut winner = None;
+
+    assert!(winner.try_emplace("alice").is_ok());
+
+    let (rejected, existing) = winner.try_emplace("bob").unwrap_err();
+    assert_eq!(rejected, "bob");
+    assert_eq!(*existing.get(), "alice");
+    ```
+    */
+    #[inline]
+    fn try_emplace(&mut self, item: T) -> Result<Occupied<'_, T>, (T, Occupied<'_, T>)> {
+        match self.entry() {
+            Entry::Occupied(occupied) => Err((item, occupied)),
+            Entry::Vacant(vacant) => Ok(vacant.insert(item)),
+        }
+    }
+
     /**
     Insert the `item` into the option, but only if the option is vacant. Either
     way, return an [`Occupied`] reference to the now-occupied [`Option`].
@@ -525,7 +2672,7 @@ impl<T> OptionExt<T> for Option<T> {
         *self = Some(item);
 
         // Safety: option is definitely Some at this point
-        unsafe { Occupied::new_unchecked(self) }
+        new_unchecked!(Occupied, self)
     }
 
     #[inline]
@@ -535,6 +2682,6 @@ impl<T> OptionExt<T> for Option<T> {
         }
 
         // Safety: option is definitely Some at this point
-        unsafe { Occupied::new_unchecked(self) }
+        new_unchecked!(Occupied, self)
     }
 }
@@ -0,0 +1,163 @@
+/*!
+An iterator adapter with multiple slots of lookahead.
+*/
+
+use crate::{Entry, examine};
+
+/**
+An iterator adapter that can look up to `N` items ahead, like a
+[`Peekable`][core::iter::Peekable] with `N` slots. Each upcoming item is held
+in its own slot, and can be accessed with an [`Entry`] from
+[`peek_entry`][Self::peek_entry], which pulls items from the underlying
+iterator on demand.
+
+Buffered items always stay in order: if an item is removed through an
+[`Entry`], the items after it move up to close the gap, and the next item from
+the underlying iterator is buffered after all of them. A vacant slot left by
+an exhausted iterator can still be filled through its [`Entry`], to push an
+item back.
+
+# Example
+
+```
+use occupied::LookaheadBuffer;
+
+let mut tokens: LookaheadBuffer<_, 2> = LookaheadBuffer::new(["let", "x", "=", "1"]);
+
+// LL(2): decide what to do based on the next 2 tokens
+assert_eq!(tokens.peek(0), Some(&"let"));
+assert_eq!(tokens.peek(1), Some(&"x"));
+tokens.consume(2);
+
+assert_eq!(tokens.next(), Some("="));
+assert_eq!(tokens.peek(1), None);
+
+// Push an item back after the end
+tokens.peek_entry(1).unwrap().or_insert(";");
+assert_eq!(tokens.collect::<Vec<_>>(), ["1", ";"]);
+```
+
+Removing an item from the middle of the lookahead:
+
+```
+use occupied::LookaheadBuffer;
+
+let mut items: LookaheadBuffer<_, 2> = LookaheadBuffer::new(1..=5);
+
+// Drop the 2nd item, keeping the 1st
+assert_eq!(items.peek_entry(1).unwrap().into_inner().take(), Some(2));
+assert_eq!(items.peek(1), Some(&3));
+
+// Then take the 1st through its entry
+assert_eq!(items.peek_entry(0).unwrap().into_inner().take(), Some(1));
+assert_eq!(items.collect::<Vec<_>>(), [3, 4, 5]);
+```
+*/
+#[derive(Debug, Clone)]
+pub struct LookaheadBuffer<I: Iterator, const N: usize> {
+    iter: I,
+
+    // A ring buffer of upcoming items; `buffer[head]` holds the next item.
+    buffer: [Option<I::Item>; N],
+    head: usize,
+}
+
+impl<I: Iterator, const N: usize> LookaheadBuffer<I, N> {
+    /// Create a new [`LookaheadBuffer`] over the items of `iter`. No items
+    /// are pulled from `iter` until they're needed.
+    #[inline]
+    #[must_use]
+    pub fn new(iter: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            iter: iter.into_iter(),
+            buffer: [const { None }; N],
+            head: 0,
+        }
+    }
+
+    /// Move buffered items up to close any gaps left by items removed
+    /// through an [`Entry`], preserving their order. Returns the number of
+    /// buffered items.
+    fn compact(&mut self) -> usize {
+        let mut filled = 0;
+
+        for offset in 0..N {
+            let index = (self.head + offset) % N;
+
+            if self.buffer[index].is_some() {
+                if offset != filled {
+                    self.buffer[(self.head + filled) % N] = self.buffer[index].take();
+                }
+
+                filled += 1;
+            }
+        }
+
+        filled
+    }
+
+    /**
+    Get an [`Entry`] for the slot holding the `k`th upcoming item (counting
+    from 0), first buffering items from the underlying iterator up to and
+    including it. The entry is only vacant if the iterator is exhausted.
+    Returns [`None`] if `k` is out of range (that is, if `k >= N`).
+    */
+    #[inline]
+    #[must_use]
+    pub fn peek_entry(&mut self, k: usize) -> Option<Entry<'_, I::Item>> {
+        if k >= N {
+            return None;
+        }
+
+        // Only ever fill from the tail, so that newly pulled items always
+        // come after the ones already buffered
+        for offset in self.compact()..=k {
+            match self.iter.next() {
+                Some(item) => self.buffer[(self.head + offset) % N] = Some(item),
+                None => break,
+            }
+        }
+
+        Some(examine(&mut self.buffer[(self.head + k) % N]))
+    }
+
+    /// Get a reference to the `k`th upcoming item (counting from 0), or
+    /// [`None`] if the iterator is exhausted before it or `k` is out of
+    /// range.
+    #[inline]
+    #[must_use]
+    pub fn peek(&mut self, k: usize) -> Option<&I::Item> {
+        match self.peek_entry(k)? {
+            Entry::Occupied(occupied) => Some(occupied.into_mut()),
+            Entry::Vacant(_) => None,
+        }
+    }
+
+    /// Unconditionally drop the next `k` items, which is typically done
+    /// after they've been peeked and matched. Items that haven't been
+    /// peeked are pulled from the underlying iterator and dropped.
+    #[inline]
+    pub fn consume(&mut self, k: usize) {
+        for _ in 0..k {
+            self.next();
+        }
+    }
+}
+
+impl<I: Iterator, const N: usize> Iterator for LookaheadBuffer<I, N> {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        match self.peek_entry(0) {
+            None => self.iter.next(),
+            Some(entry) => {
+                // Only advance past a slot that actually held an item; a
+                // vacant head means the iterator is exhausted
+                let item = entry.into_inner().take()?;
+                self.head = (self.head + 1) % N;
+                Some(item)
+            }
+        }
+    }
+}
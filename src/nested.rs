@@ -0,0 +1,84 @@
+/*!
+Proofs over nested options, like `Option<Option<T>>`, which are common when
+modelling "known to be absent" separately from "unknown".
+*/
+
+use crate::{Entry, Occupied, Vacant, examine};
+
+/**
+Wrapper around a mutable reference to a nested option, containing
+information about which of its three states it's in. Created with
+[`examine_nested`].
+
+# Example
+
+```
+use occupied::{NestedEntry, examine_nested};
+
+// `None`: not looked up yet; `Some(None)`: known to be missing
+let mut cache: Option<Option<u32>> = None;
+
+fn lookup(cache: &mut Option<Option<u32>>, found: Option<u32>) -> Option<u32> {
+    match examine_nested(cache) {
+        NestedEntry::OuterVacant(vacant) => *vacant.insert(found).get(),
+        NestedEntry::InnerVacant(_) => None,
+        NestedEntry::Occupied(occupied) => Some(*occupied.get()),
+    }
+}
+
+assert_eq!(lookup(&mut cache, None), None);
+assert_eq!(cache, Some(None));
+
+// The cached absence is remembered
+assert_eq!(lookup(&mut cache, Some(1)), None);
+```
+*/
+#[derive(Debug)]
+pub enum NestedEntry<'a, T> {
+    /// The outer option is vacant
+    OuterVacant(Vacant<'a, Option<T>>),
+
+    /// The outer option is occupied, but the inner option is vacant
+    InnerVacant(Vacant<'a, T>),
+
+    /// Both options are occupied
+    Occupied(Occupied<'a, T>),
+}
+
+/**
+Examine a nested option and return a [`NestedEntry`] describing which of its
+three states it's in.
+*/
+#[inline]
+pub const fn examine_nested<T>(option: &mut Option<Option<T>>) -> NestedEntry<'_, T> {
+    match examine(option) {
+        Entry::Vacant(vacant) => NestedEntry::OuterVacant(vacant),
+        Entry::Occupied(occupied) => match occupied.flatten() {
+            Entry::Vacant(vacant) => NestedEntry::InnerVacant(vacant),
+            Entry::Occupied(occupied) => NestedEntry::Occupied(occupied),
+        },
+    }
+}
+
+impl<'a, T> Occupied<'a, Option<T>> {
+    /**
+    For an option holding another option, get an [`Entry`] for the inner
+    option, with the original lifetime.
+
+    # Example
+
+    ```
+    use occupied::OptionExt as _;
+
+    let mut opt = Some(None);
+
+    opt.peek_some().unwrap().flatten().or_insert(1);
+    assert_eq!(opt, Some(Some(1)));
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub const fn flatten(self) -> Entry<'a, T> {
+        examine(self.into_mut())
+    }
+}
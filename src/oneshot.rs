@@ -0,0 +1,272 @@
+/*!
+A single-use channel, which sends exactly one value from a [`Sender`] to a
+[`Receiver`] through a shared slot. The receiver can either block the current
+thread until the value arrives ([`Receiver::recv`]), or be `.await`ed.
+
+The channel requires the `std` feature, including for the async receiver: the
+value passes through a [`Mutex`]-guarded slot in an [`Arc`], and
+[`recv`][Receiver::recv] waits on a [`Condvar`].
+
+# Example
+
+```
+use std::thread;
+
+let (sender, receiver) = occupied::oneshot();
+
+thread::spawn(move || sender.send(10).unwrap());
+assert_eq!(receiver.recv(), Ok(10));
+
+// Dropping the sender without sending is reported as an error
+let (sender, receiver) = occupied::oneshot::<i32>();
+drop(sender);
+assert_eq!(receiver.recv(), Err(occupied::oneshot::RecvError));
+```
+*/
+
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use std::{
+    error::Error,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::OptionExt as _;
+
+struct State<T> {
+    slot: Option<T>,
+    waker: Option<Waker>,
+    sender_dropped: bool,
+    receiver_dropped: bool,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    ready: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        // No user code runs while the lock is held, so poisoning is
+        // impossible in practice, and harmless anyway.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/**
+Create a new oneshot channel, returning the [`Sender`] and [`Receiver`]
+halves.
+*/
+#[must_use]
+pub fn oneshot<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            slot: None,
+            waker: None,
+            sender_dropped: false,
+            receiver_dropped: false,
+        }),
+        ready: Condvar::new(),
+    });
+
+    (
+        Sender {
+            shared: Arc::clone(&shared),
+        },
+        Receiver { shared },
+    )
+}
+
+/// The sending half of a [`oneshot`] channel.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /**
+    Send a value to the [`Receiver`]. Returns the value back if the receiver
+    has already been dropped.
+    */
+    pub fn send(self, value: T) -> Result<(), T> {
+        let mut state = self.shared.lock();
+
+        if state.receiver_dropped {
+            return Err(value);
+        }
+
+        // Waking the receiver is handled by `drop`, which runs right after
+        // this
+        state.slot.emplace(value);
+        Ok(())
+    }
+
+    /// Returns true if the [`Receiver`] has been dropped, meaning that any
+    /// value sent would be returned.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.shared.lock().receiver_dropped
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut state = self.shared.lock();
+            state.sender_dropped = true;
+            state.waker.take()
+        };
+
+        self.shared.ready.notify_all();
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/**
+The receiving half of a [`oneshot`] channel. Use [`recv`][Receiver::recv]
+to block until the value arrives, or `.await` it.
+*/
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Block the current thread until the value arrives. Returns an error if
+    /// the [`Sender`] was dropped without sending a value.
+    pub fn recv(self) -> Result<T, RecvError> {
+        let mut state = self.shared.lock();
+
+        loop {
+            if let Some(value) = state.slot.take() {
+                return Ok(value);
+            }
+
+            if state.sender_dropped {
+                return Err(RecvError);
+            }
+
+            state = self
+                .shared
+                .ready
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /**
+    Receive the value, if it has arrived, without blocking. Returns
+    [`Empty`][TryRecvError::Empty] if the value hasn't been sent yet, and
+    [`Disconnected`][TryRecvError::Disconnected] if the [`Sender`] was
+    dropped without sending one.
+
+    Since sending consumes the [`Sender`], the channel is disconnected once
+    the value has been received, so calling this again after a successful
+    receive returns [`Disconnected`][TryRecvError::Disconnected].
+
+    # Example
+
+    ```
+    use occupied::oneshot::TryRecvError;
+
+    let (sender, mut receiver) = occupied::oneshot();
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+    sender.send("hello").unwrap();
+    assert_eq!(receiver.try_recv(), Ok("hello"));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    ```
+    */
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.shared.lock();
+
+        match state.slot.take() {
+            Some(value) => Ok(value),
+            None if state.sender_dropped => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.lock();
+
+        if let Some(value) = state.slot.take() {
+            return Poll::Ready(Ok(value));
+        }
+
+        if state.sender_dropped {
+            return Poll::Ready(Err(RecvError));
+        }
+
+        match state.waker {
+            Some(ref waker) if waker.will_wake(cx.waker()) => {}
+            _ => state.waker = Some(cx.waker().clone()),
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.lock().receiver_dropped = true;
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+/// Error returned when receiving from a [`oneshot`] channel whose [`Sender`]
+/// was dropped without sending a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecvError;
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("oneshot sender was dropped without sending a value")
+    }
+}
+
+impl Error for RecvError {}
+
+/// Error returned by [`Receiver::try_recv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TryRecvError {
+    /// The value hasn't been sent yet.
+    Empty,
+
+    /// The [`Sender`] was dropped without sending a value.
+    Disconnected,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("oneshot value hasn't been sent yet"),
+            TryRecvError::Disconnected => {
+                f.write_str("oneshot sender was dropped without sending a value")
+            }
+        }
+    }
+}
+
+impl Error for TryRecvError {}
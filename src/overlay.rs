@@ -0,0 +1,55 @@
+/*!
+Layering of configuration-like structs made of optional fields.
+*/
+
+use crate::OptionExt as _;
+
+/**
+Types that can be layered on top of each other, where the values in one layer
+take priority over the values in another, and only missing values are filled
+in from the lower-priority layer.
+
+For [`Option`], this means that the other option is only used if `self` is
+vacant. For structs made of options, this can be derived with
+`#[derive(OverlayOptions)]` when the `derive` feature is enabled; each field
+is then overlaid individually. A typical use is merging configuration from
+several sources, such as command line > environment > file > defaults.
+
+# Example
+
+```
+use occupied::OverlayOptions;
+
+let cli = Some(30);
+let env = None;
+let file = Some(10);
+
+assert_eq!(env.overlay(file), Some(10));
+assert_eq!(cli.overlay(env).overlay(file), Some(30));
+```
+*/
+pub trait OverlayOptions: Sized {
+    /// Fill in every vacant part of `self` with the corresponding part of
+    /// `other`. Anything that's already occupied in `self` is left alone.
+    fn fill_vacant_from(&mut self, other: Self);
+
+    /// Layer `self` on top of `other`, so that anything vacant in `self` is
+    /// filled in from `other`.
+    #[inline]
+    #[must_use]
+    fn overlay(mut self, other: Self) -> Self {
+        self.fill_vacant_from(other);
+        self
+    }
+}
+
+impl<T> OverlayOptions for Option<T> {
+    #[inline]
+    fn fill_vacant_from(&mut self, other: Self) {
+        if let Some(vacant) = self.peek_empty()
+            && let Some(item) = other
+        {
+            vacant.insert(item);
+        }
+    }
+}
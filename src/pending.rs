@@ -0,0 +1,167 @@
+/*!
+A slot holding a current value alongside a staged replacement.
+*/
+
+use crate::{DynSlot, Entry, Occupied, OptionExt as _, examine};
+
+/**
+A pair of slots: a *current* value, and a *pending* value which has been
+staged to replace it. The pending value only becomes current when it's
+[committed][PendingSlot::commit], and can be [discarded][PendingSlot::discard]
+in the meantime; either way, the current value remains readable throughout.
+
+This is the shape of things like settings dialogs (edit, then apply or
+cancel) and transactional config reloads.
+
+# Example
+
+```
+use occupied::PendingSlot;
+
+let mut slot = PendingSlot::with_current(10);
+
+slot.stage(20);
+assert_eq!(slot.current(), Some(&10));
+assert_eq!(slot.pending(), Some(&20));
+
+slot.discard();
+assert_eq!(slot.current(), Some(&10));
+assert_eq!(slot.pending(), None);
+
+slot.stage(30);
+assert_eq!(slot.commit(), Some(10));
+assert_eq!(slot.current(), Some(&30));
+assert_eq!(slot.pending(), None);
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PendingSlot<T> {
+    current: Option<T>,
+    pending: Option<T>,
+}
+
+impl<T> PendingSlot<T> {
+    /// Create a new [`PendingSlot`] with neither a current nor a pending
+    /// value.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            current: None,
+            pending: None,
+        }
+    }
+
+    /// Create a new [`PendingSlot`] with a current value and no pending
+    /// value.
+    #[inline]
+    #[must_use]
+    pub const fn with_current(value: T) -> Self {
+        Self {
+            current: Some(value),
+            pending: None,
+        }
+    }
+
+    /// Get a reference to the current value, if any.
+    #[inline]
+    #[must_use]
+    pub const fn current(&self) -> Option<&T> {
+        self.current.as_ref()
+    }
+
+    /// Get a reference to the pending value, if any.
+    #[inline]
+    #[must_use]
+    pub const fn pending(&self) -> Option<&T> {
+        self.pending.as_ref()
+    }
+
+    /// Returns true if there is a pending value waiting to be committed.
+    #[inline]
+    #[must_use]
+    pub const fn has_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Try to get an [`Occupied`] reference to the current value. Returns
+    /// [`None`] if there is no current value.
+    #[inline]
+    #[must_use]
+    pub fn peek_current(&mut self) -> Option<Occupied<'_, T>> {
+        self.current.peek_some()
+    }
+
+    /// Try to get an [`Occupied`] reference to the pending value. Returns
+    /// [`None`] if nothing is staged.
+    #[inline]
+    #[must_use]
+    pub fn peek_pending(&mut self) -> Option<Occupied<'_, T>> {
+        self.pending.peek_some()
+    }
+
+    /// Get an [`Entry`] for the current value.
+    #[inline]
+    #[must_use]
+    pub const fn current_entry(&mut self) -> Entry<'_, T> {
+        examine(&mut self.current)
+    }
+
+    /// Get an [`Entry`] for the pending value.
+    #[inline]
+    #[must_use]
+    pub const fn pending_entry(&mut self) -> Entry<'_, T> {
+        examine(&mut self.pending)
+    }
+
+    /**
+    Stage a value to replace the current value when this slot is next
+    [committed][Self::commit], and return an [`Occupied`] reference to the
+    staged value. Any previously staged value is dropped.
+    */
+    #[inline]
+    pub fn stage(&mut self, value: T) -> Occupied<'_, T> {
+        self.pending.emplace(value)
+    }
+
+    /**
+    Commit the pending value, if any, making it the current value. Returns
+    the value it replaced, if any. Does nothing (and returns [`None`]) if
+    there's no pending value.
+    */
+    #[inline]
+    pub fn commit(&mut self) -> Option<T> {
+        match self.pending.peek_some() {
+            Some(pending) => self.current.replace(pending.take()),
+            None => None,
+        }
+    }
+
+    /// Discard the pending value, if any, leaving the current value as it
+    /// is. Returns the discarded value.
+    #[inline]
+    pub const fn discard(&mut self) -> Option<T> {
+        self.pending.take()
+    }
+
+    /// Consume this slot, returning the current and pending values.
+    #[inline]
+    #[must_use]
+    pub fn into_parts(self) -> (Option<T>, Option<T>) {
+        (self.current, self.pending)
+    }
+}
+
+impl<T: 'static> DynSlot for PendingSlot<T> {
+    /// A [`PendingSlot`] is occupied if it has a current value.
+    #[inline]
+    fn is_occupied(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Clear both the current and pending values.
+    #[inline]
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+}
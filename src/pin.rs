@@ -0,0 +1,291 @@
+/*!
+Proofs over pinned options, for options holding `!Unpin` values such as
+futures or intrusive nodes. These mirror [`Occupied`] and [`Vacant`], but
+only ever expose the item through [`Pin`], so they're usable with any `T`.
+
+# Example
+
+```
+use core::marker::PhantomPinned;
+use core::pin::pin;
+use occupied::pin::{PinEntry, examine_pin};
+
+struct Node {
+    value: i32,
+    _pinned: PhantomPinned,
+}
+
+let mut slot = pin!(None);
+
+let PinEntry::Vacant(vacant) = examine_pin(slot.as_mut()) else {
+    unreachable!()
+};
+let occupied = vacant.insert(Node { value: 1, _pinned: PhantomPinned });
+assert_eq!(occupied.get().value, 1);
+
+// Dropping the item in place is always allowed
+let PinEntry::Occupied(occupied) = examine_pin(slot.as_mut()) else {
+    unreachable!()
+};
+occupied.clear();
+assert!(slot.is_none());
+```
+*/
+
+use core::pin::Pin;
+
+use crate::{Occupied, Vacant};
+
+/// As with [`Occupied`] and [`Vacant`], the `option` field is only directly
+/// accessible in here, to force the use of `unsafe{}` to construct the
+/// proofs.
+mod internals {
+    use core::pin::Pin;
+
+    /**
+    A pinned reference to an [`Option`] that is statically guaranteed to be
+    occupied. The item can only be accessed through [`Pin`], unless it's
+    [`Unpin`].
+    */
+    #[derive(Debug)]
+    pub struct PinnedOccupied<'a, T> {
+        option: Pin<&'a mut Option<T>>,
+    }
+
+    impl<'a, T> PinnedOccupied<'a, T> {
+        /**
+        Create a new [`PinnedOccupied`], referencing a pinned [`Option`] that
+        is definitely [`Some`].
+
+        # Safety
+
+        The `option` parameter MUST be [`Some`].
+        */
+        #[inline(always)]
+        #[must_use]
+        #[allow(unsafe_code)]
+        pub const unsafe fn new_unchecked(option: Pin<&'a mut Option<T>>) -> Self {
+            Self { option }
+        }
+
+        /// Safe equivalent of [`new_unchecked`][Self::new_unchecked], used
+        /// internally when `forbid-unsafe` is enabled.
+        #[cfg(feature = "forbid-unsafe")]
+        #[inline(always)]
+        #[must_use]
+        pub(crate) const fn new_trusted(option: Pin<&'a mut Option<T>>) -> Self {
+            Self { option }
+        }
+
+        /**
+        Get the underlying pinned [`Option`]. This destroys `self`, because
+        we lose the guarantee that the option is occupied.
+        */
+        #[inline(always)]
+        #[must_use]
+        pub fn into_inner(self) -> Pin<&'a mut Option<T>> {
+            self.option
+        }
+
+        /// Get a shared reference to the underlying [`Option`].
+        #[inline(always)]
+        #[must_use]
+        pub fn as_option(&self) -> &Option<T> {
+            self.option.as_ref().get_ref()
+        }
+
+        /// Reborrow the underlying pinned [`Option`], without destroying
+        /// `self`. Only used internally, since callers could use it to break
+        /// the invariant.
+        #[inline(always)]
+        pub(crate) fn as_option_mut(&mut self) -> Pin<&mut Option<T>> {
+            self.option.as_mut()
+        }
+    }
+
+    /// A pinned reference to an [`Option`] that is statically guaranteed to
+    /// be vacant.
+    #[derive(Debug)]
+    pub struct PinnedVacant<'a, T> {
+        option: Pin<&'a mut Option<T>>,
+    }
+
+    impl<'a, T> PinnedVacant<'a, T> {
+        /**
+        Create a new [`PinnedVacant`], referencing a pinned [`Option`] that
+        is definitely [`None`].
+
+        # Safety
+
+        The `option` parameter MUST be [`None`].
+        */
+        #[inline(always)]
+        #[must_use]
+        #[allow(unsafe_code)]
+        pub const unsafe fn new_unchecked(option: Pin<&'a mut Option<T>>) -> Self {
+            Self { option }
+        }
+
+        /// Safe equivalent of [`new_unchecked`][Self::new_unchecked], used
+        /// internally when `forbid-unsafe` is enabled.
+        #[cfg(feature = "forbid-unsafe")]
+        #[inline(always)]
+        #[must_use]
+        pub(crate) const fn new_trusted(option: Pin<&'a mut Option<T>>) -> Self {
+            Self { option }
+        }
+
+        /**
+        Get the underlying pinned [`Option`]. This destroys `self`, because
+        we lose the guarantee that the option is vacant.
+        */
+        #[inline(always)]
+        #[must_use]
+        pub fn into_inner(self) -> Pin<&'a mut Option<T>> {
+            self.option
+        }
+    }
+}
+
+pub use internals::{PinnedOccupied, PinnedVacant};
+
+/**
+Wrapper around a pinned reference to an option, containing information about
+whether the option is vacant or occupied.
+*/
+#[derive(Debug)]
+pub enum PinEntry<'a, T> {
+    /// The option is occupied
+    Occupied(PinnedOccupied<'a, T>),
+
+    /// The option is vacant
+    Vacant(PinnedVacant<'a, T>),
+}
+
+/**
+Examine a pinned option and return either a [`PinnedOccupied`] reference, if
+it's occupied, or a [`PinnedVacant`] reference, if it's vacant. This is the
+pinned equivalent of [`examine`][crate::examine].
+*/
+#[inline]
+pub fn examine_pin<T>(option: Pin<&mut Option<T>>) -> PinEntry<'_, T> {
+    match option.is_some() {
+        true => PinEntry::Occupied(new_unchecked!(PinnedOccupied, option)),
+        false => PinEntry::Vacant(new_unchecked!(PinnedVacant, option)),
+    }
+}
+
+impl<'a, T> PinnedOccupied<'a, T> {
+    /// Try to create a new [`PinnedOccupied`] instance, referencing a pinned
+    /// [`Option`] that is definitely [`Some`]. Returns [`None`] if the option
+    /// is [`None`].
+    #[inline]
+    #[must_use]
+    pub fn new(option: Pin<&'a mut Option<T>>) -> Option<Self> {
+        match examine_pin(option) {
+            PinEntry::Occupied(occupied) => Some(occupied),
+            PinEntry::Vacant(_) => None,
+        }
+    }
+
+    /// Get an immutable reference to the data in the referenced option.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> &T {
+        // Safety: the option in a `PinnedOccupied` is guaranteed to be `Some`
+        unwrap_unchecked!(self.as_option().as_ref())
+    }
+
+    /// Get a pinned mutable reference to the data in the referenced option,
+    /// like [`Option::as_pin_mut`].
+    #[inline]
+    #[must_use]
+    pub fn get_pin_mut(&mut self) -> Pin<&mut T> {
+        // Safety: the option in a `PinnedOccupied` is guaranteed to be `Some`
+        unwrap_unchecked!(self.as_option_mut().as_pin_mut())
+    }
+
+    /// Get a pinned mutable reference to the data in the referenced option,
+    /// with the original lifetime.
+    #[inline]
+    #[must_use]
+    pub fn into_pin_mut(self) -> Pin<&'a mut T> {
+        // Safety: the option in a `PinnedOccupied` is guaranteed to be `Some`
+        unwrap_unchecked!(self.into_inner().as_pin_mut())
+    }
+
+    /// Replace the item in the option with `value`, dropping the old item in
+    /// place, as with [`Pin::set`]. The option remains occupied.
+    #[inline]
+    pub fn set(&mut self, value: T) {
+        self.as_option_mut().set(Some(value));
+    }
+
+    /// Drop the item in place, leaving [`None`] in its place, and return a
+    /// [`PinnedVacant`] reference to the now-vacant option.
+    #[inline]
+    pub fn clear(self) -> PinnedVacant<'a, T> {
+        let mut option = self.into_inner();
+        option.set(None);
+
+        // Safety: we just cleared the option
+        new_unchecked!(PinnedVacant, option)
+    }
+}
+
+impl<'a, T: Unpin> PinnedOccupied<'a, T> {
+    /// Convert this into a regular [`Occupied`] reference. Items which are
+    /// [`Unpin`] can be freely moved, so this gives full access to the item.
+    #[inline]
+    #[must_use]
+    pub fn into_occupied(self) -> Occupied<'a, T> {
+        let option = Pin::into_inner(self.into_inner());
+
+        // Safety: the option in a `PinnedOccupied` is guaranteed to be `Some`
+        new_unchecked!(Occupied, option)
+    }
+
+    /// Remove the item from the option, leaving [`None`] in its place. This
+    /// is only possible if the item is [`Unpin`].
+    #[inline]
+    pub fn take(self) -> T {
+        self.into_occupied().take()
+    }
+}
+
+impl<'a, T> PinnedVacant<'a, T> {
+    /// Try to create a new [`PinnedVacant`] instance, referencing a pinned
+    /// [`Option`] that is definitely [`None`]. Returns [`None`] if the option
+    /// is [`Some`].
+    #[inline]
+    #[must_use]
+    pub fn new(option: Pin<&'a mut Option<T>>) -> Option<Self> {
+        match examine_pin(option) {
+            PinEntry::Occupied(_) => None,
+            PinEntry::Vacant(vacant) => Some(vacant),
+        }
+    }
+
+    /// Insert an item into the option, and return a [`PinnedOccupied`]
+    /// reference to the now-occupied option.
+    #[inline]
+    pub fn insert(self, value: T) -> PinnedOccupied<'a, T> {
+        let mut option = self.into_inner();
+        option.set(Some(value));
+
+        // Safety: we just inserted an item into the option
+        new_unchecked!(PinnedOccupied, option)
+    }
+}
+
+impl<'a, T: Unpin> PinnedVacant<'a, T> {
+    /// Convert this into a regular [`Vacant`] reference.
+    #[inline]
+    #[must_use]
+    pub fn into_vacant(self) -> Vacant<'a, T> {
+        let option = Pin::into_inner(self.into_inner());
+
+        // Safety: the option in a `PinnedVacant` is guaranteed to be `None`
+        new_unchecked!(Vacant, option)
+    }
+}
@@ -0,0 +1,265 @@
+/*!
+A fixed-capacity blocking queue, stored in a ring of option slots.
+*/
+
+use core::{fmt, mem};
+
+use std::{
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+    vec::Vec,
+};
+
+use crate::{Occupied, OccupiedSlice, Vacant};
+
+struct Ring<T, const N: usize> {
+    // `slots[head]` holds the oldest item, and the `len` slots after it
+    // (wrapping around) are occupied. The rest are vacant.
+    slots: [Option<T>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> Ring<T, N> {
+    fn push(&mut self, item: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(item);
+        }
+
+        let index = (self.head + self.len) % N;
+        self.len += 1;
+
+        // Safety: the slots after the `len` occupied ones are vacant
+        new_unchecked!(Vacant, &mut self.slots[index]).insert(item);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // Safety: `len` is nonzero, so the slot at `head` is occupied
+        let item = new_unchecked!(Occupied, &mut self.slots[self.head]).take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(item)
+    }
+}
+
+/**
+A fixed-capacity, multi-producer, multi-consumer queue which blocks when
+it's full or empty. Its storage is a ring of `N` option slots, guarded by a
+mutex, which are filled and emptied through [`Vacant`] and [`Occupied`]
+proofs. It can be shared between threads by reference (for instance, with
+[`std::thread::scope`]) or in an [`Arc`][std::sync::Arc].
+
+The occupancy statistics are [`len`][Self::len], [`is_empty`][Self::is_empty],
+and [`is_full`][Self::is_full]. Each of them takes the lock, and the result is
+only a snapshot, which other threads may change immediately afterwards.
+
+# Example
+
+```
+use std::thread;
+use occupied::BoundedQueue;
+
+let queue: BoundedQueue<i32, 4> = BoundedQueue::new();
+
+thread::scope(|scope| {
+    scope.spawn(|| (0..10).for_each(|item| queue.push(item)));
+
+    let received: Vec<i32> = (0..10).map(|_| queue.pop()).collect();
+    assert_eq!(received, (0..10).collect::<Vec<_>>());
+});
+
+assert!(queue.is_empty());
+```
+*/
+pub struct BoundedQueue<T, const N: usize> {
+    ring: Mutex<Ring<T, N>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl<T, const N: usize> BoundedQueue<T, N> {
+    /// Create a new, empty [`BoundedQueue`]. `N` must not be 0.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        const { assert!(N > 0, "a BoundedQueue must have a nonzero capacity") };
+
+        Self {
+            ring: Mutex::new(Ring {
+                slots: [const { None }; N],
+                head: 0,
+                len: 0,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Ring<T, N>> {
+        // No user code runs while the lock is held, so poisoning is
+        // impossible in practice, and harmless anyway.
+        self.ring.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Push an item onto the back of the queue, blocking the current thread
+    /// until there's room for it.
+    pub fn push(&self, mut item: T) {
+        let mut ring = self.lock();
+
+        loop {
+            match ring.push(item) {
+                Ok(()) => break,
+                Err(rejected) => {
+                    item = rejected;
+                    ring = self
+                        .not_full
+                        .wait(ring)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        }
+
+        drop(ring);
+        self.not_empty.notify_one();
+    }
+
+    /// Push an item onto the back of the queue, if there's room for it.
+    /// Returns the item back if the queue is full.
+    pub fn try_push(&self, item: T) -> Result<(), T> {
+        self.lock().push(item)?;
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Pop the item at the front of the queue, blocking the current thread
+    /// until there is one.
+    pub fn pop(&self) -> T {
+        let mut ring = self.lock();
+
+        let item = loop {
+            match ring.pop() {
+                Some(item) => break item,
+                None => {
+                    ring = self
+                        .not_empty
+                        .wait(ring)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        };
+
+        drop(ring);
+        self.not_full.notify_one();
+        item
+    }
+
+    /// Pop the item at the front of the queue, if there is one.
+    pub fn try_pop(&self) -> Option<T> {
+        let item = self.lock().pop()?;
+        self.not_full.notify_one();
+        Some(item)
+    }
+
+    /**
+    Pop as many items as are available (up to the length of `dest`) without
+    blocking, moving them in order into the front of `dest` and replacing
+    anything already there. Returns an [`OccupiedSlice`] proof over the
+    filled prefix of `dest`, which is empty if the queue was empty.
+
+    # Example
+
+    ```
+    use occupied::BoundedQueue;
+
+    let queue: BoundedQueue<i32, 8> = BoundedQueue::new();
+    (1..=5).for_each(|item| queue.push(item));
+
+    let mut batch = [None; 3];
+    let drained = queue.drain_into(&mut batch);
+    assert_eq!(drained.iter().sum::<i32>(), 6);
+
+    assert_eq!(queue.len(), 2);
+    ```
+    */
+    pub fn drain_into<'a>(&self, dest: &'a mut [Option<T>]) -> OccupiedSlice<'a, T> {
+        let mut ring = self.lock();
+        let count = Ord::min(ring.len, dest.len());
+
+        // Move any old values out of `dest` rather than dropping them here,
+        // so that their destructors don't run while the lock is held. This
+        // only allocates if `dest` wasn't already vacant.
+        let mut replaced = Vec::new();
+
+        for slot in &mut dest[..count] {
+            if let Some(old) = mem::replace(slot, ring.pop()) {
+                replaced.push(old);
+            }
+        }
+
+        drop(ring);
+        self.not_full.notify_all();
+        drop(replaced);
+
+        // Safety: the ring had at least `count` items, so every one of the
+        // first `count` slots was filled
+        new_unchecked!(OccupiedSlice, &mut dest[..count])
+    }
+
+    /// Get the number of items currently in the queue. This takes the lock.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len
+    }
+
+    /// Returns true if the queue is currently empty.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if the queue is currently full.
+    #[inline]
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Get the maximum number of items the queue can hold, which is `N`.
+    #[inline]
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Consume the queue, returning an iterator over the remaining items,
+    /// from front to back.
+    pub fn into_items(self) -> impl Iterator<Item = T> {
+        let mut ring = self
+            .ring
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        core::iter::from_fn(move || ring.pop())
+    }
+}
+
+impl<T, const N: usize> Default for BoundedQueue<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for BoundedQueue<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedQueue")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish_non_exhaustive()
+    }
+}
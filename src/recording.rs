@@ -0,0 +1,185 @@
+/*!
+A slot that logs every operation performed on it, for use in tests.
+*/
+
+use alloc::vec::Vec;
+
+use crate::{DynSlot, Entry, Occupied, OptionExt as _, examine};
+
+/// An operation performed on a [`RecordingSlot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotOp {
+    /// The value was accessed with [`get`][RecordingSlot::get].
+    Get,
+
+    /// The value was accessed with [`get_mut`][RecordingSlot::get_mut].
+    GetMut,
+
+    /// A value was inserted with [`insert`][RecordingSlot::insert].
+    Insert,
+
+    /// The value was removed with [`take`][RecordingSlot::take].
+    Take,
+
+    /// An [`Entry`] was created with [`entry`][RecordingSlot::entry].
+    Entry,
+}
+
+/// A single operation in the log of a [`RecordingSlot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SlotRecord {
+    /// The sequence number of this operation. Sequence numbers start at 0
+    /// and keep counting up even if the log is
+    /// [cleared][RecordingSlot::clear_log].
+    pub seq: u64,
+
+    /// The operation that was performed.
+    pub op: SlotOp,
+
+    /// Whether the slot was occupied when the operation was performed.
+    pub occupied: bool,
+}
+
+/**
+An optional value that behaves like a plain [`Option`], but records every
+operation performed on it, so that tests can assert that code under test
+touches it in the expected order.
+
+Only operations performed through the [`RecordingSlot`] itself are recorded;
+changes made through an [`Occupied`] reference or an [`Entry`] after it's
+been handed out aren't.
+
+# Example
+
+```
+use occupied::{RecordingSlot, SlotOp};
+
+let mut slot = RecordingSlot::new();
+
+slot.entry().or_insert(1);
+assert_eq!(slot.get(), Some(&1));
+slot.take();
+
+let ops: Vec<_> = slot.log().iter().map(|record| (record.op, record.occupied)).collect();
+assert_eq!(ops, [(SlotOp::Entry, false), (SlotOp::Get, true), (SlotOp::Take, true)]);
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct RecordingSlot<T> {
+    slot: Option<T>,
+    log: Vec<SlotRecord>,
+    next_seq: u64,
+}
+
+impl<T> RecordingSlot<T> {
+    /// Create a new, vacant [`RecordingSlot`] with an empty log.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            slot: None,
+            log: Vec::new(),
+            next_seq: 0,
+        }
+    }
+
+    /// Create a new [`RecordingSlot`] containing `value`, with an empty log.
+    #[inline]
+    #[must_use]
+    pub const fn with_value(value: T) -> Self {
+        Self {
+            slot: Some(value),
+            log: Vec::new(),
+            next_seq: 0,
+        }
+    }
+
+    /// Append an operation to the log
+    fn record(&mut self, op: SlotOp) {
+        self.log.push(SlotRecord {
+            seq: self.next_seq,
+            op,
+            occupied: self.slot.is_some(),
+        });
+        self.next_seq += 1;
+    }
+
+    /// Get a reference to the value in this slot, if any, recording a
+    /// [`SlotOp::Get`].
+    #[inline]
+    #[must_use]
+    pub fn get(&mut self) -> Option<&T> {
+        self.record(SlotOp::Get);
+        self.slot.as_ref()
+    }
+
+    /// Get a mutable reference to the value in this slot, if any, recording
+    /// a [`SlotOp::GetMut`].
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.record(SlotOp::GetMut);
+        self.slot.as_mut()
+    }
+
+    /// Insert a value into this slot, replacing any existing value, and
+    /// return an [`Occupied`] reference to it, recording a
+    /// [`SlotOp::Insert`].
+    #[inline]
+    pub fn insert(&mut self, value: T) -> Occupied<'_, T> {
+        self.record(SlotOp::Insert);
+        self.slot.emplace(value)
+    }
+
+    /// Remove the value from this slot, if any, recording a
+    /// [`SlotOp::Take`].
+    #[inline]
+    pub fn take(&mut self) -> Option<T> {
+        self.record(SlotOp::Take);
+        self.slot.take()
+    }
+
+    /// Get an [`Entry`] for this slot, recording a [`SlotOp::Entry`].
+    #[inline]
+    #[must_use]
+    pub fn entry(&mut self) -> Entry<'_, T> {
+        self.record(SlotOp::Entry);
+        examine(&mut self.slot)
+    }
+
+    /// Get the log of operations performed on this slot, in order. Reading
+    /// the log isn't recorded.
+    #[inline]
+    #[must_use]
+    pub fn log(&self) -> &[SlotRecord] {
+        &self.log
+    }
+
+    /// Clear the log of operations. Sequence numbers of later operations
+    /// continue from where they left off.
+    #[inline]
+    pub fn clear_log(&mut self) {
+        self.log.clear();
+    }
+
+    /// Consume this slot, returning the value, if any, and discarding the
+    /// log.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Option<T> {
+        self.slot
+    }
+}
+
+impl<T: 'static> DynSlot for RecordingSlot<T> {
+    #[inline]
+    fn is_occupied(&self) -> bool {
+        self.slot.is_some()
+    }
+
+    /// Remove the value from this slot, recording a [`SlotOp::Take`].
+    #[inline]
+    fn clear(&mut self) {
+        self.take();
+    }
+}
@@ -0,0 +1,182 @@
+/*!
+A ring of slots indexed by a wrapping sequence number, for reassembling items
+that arrive out of order.
+*/
+
+use crate::{Entry, Occupied, OptionExt as _, examine};
+
+/**
+A window of `N` slots, indexed by a wrapping `u32` sequence number, for
+reassembling items (such as packets or job results) that arrive out of order.
+
+The window starts at the [next expected][Self::next_seq] sequence number,
+and covers the `N` sequence numbers after it (wrapping around at
+[`u32::MAX`]). Items can be inserted anywhere in the window, and are popped
+in order with [`pop_contiguous`][Self::pop_contiguous] as soon as every
+earlier item has arrived, which advances the window.
+
+# Example
+
+```
+use occupied::SequenceSlots;
+
+let mut slots: SequenceSlots<&str, 4> = SequenceSlots::new(u32::MAX);
+
+slots.insert(0, "b").unwrap();
+slots.insert(2, "d").unwrap();
+assert_eq!(slots.pop_contiguous(), None);
+
+slots.insert(u32::MAX, "a").unwrap();
+assert_eq!(slots.drain_contiguous().collect::<Vec<_>>(), ["a", "b"]);
+assert_eq!(slots.next_seq(), 1);
+
+// 1 was lost, so give up on it
+assert_eq!(slots.skip(), None);
+assert_eq!(slots.pop_contiguous(), Some("d"));
+
+// Sequence numbers outside of the window are rejected
+assert_eq!(slots.insert(1, "stale").err(), Some("stale"));
+assert_eq!(slots.insert(7, "too far").err(), Some("too far"));
+```
+*/
+#[derive(Debug, Clone)]
+pub struct SequenceSlots<T, const N: usize> {
+    slots: [Option<T>; N],
+
+    // `slots[head]` holds the item with sequence number `base`, and the
+    // following slots (wrapping around) hold the rest of the window.
+    head: usize,
+    base: u32,
+}
+
+impl<T, const N: usize> SequenceSlots<T, N> {
+    /// Create a new, empty [`SequenceSlots`], expecting `start` as the first
+    /// sequence number.
+    #[inline]
+    #[must_use]
+    pub const fn new(start: u32) -> Self {
+        Self {
+            slots: [const { None }; N],
+            head: 0,
+            base: start,
+        }
+    }
+
+    /// Get the next expected sequence number, which is the start of the
+    /// window.
+    #[inline]
+    #[must_use]
+    pub const fn next_seq(&self) -> u32 {
+        self.base
+    }
+
+    /// Get the index in `slots` of `seq`, if it's in the window
+    #[inline]
+    const fn index(&self, seq: u32) -> Option<usize> {
+        let offset = seq.wrapping_sub(self.base) as usize;
+
+        match offset < N {
+            true => Some((self.head + offset) % N),
+            false => None,
+        }
+    }
+
+    /// Returns true if `seq` is inside the window, meaning that it can be
+    /// inserted.
+    #[inline]
+    #[must_use]
+    pub const fn in_window(&self, seq: u32) -> bool {
+        self.index(seq).is_some()
+    }
+
+    /// Returns true if the item with sequence number `seq` has arrived and
+    /// hasn't been popped yet.
+    #[inline]
+    #[must_use]
+    pub const fn contains(&self, seq: u32) -> bool {
+        match self.index(seq) {
+            Some(index) => self.slots[index].is_some(),
+            None => false,
+        }
+    }
+
+    /// Get the number of items that have arrived and haven't been popped
+    /// yet.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Returns true if no items are waiting to be popped.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    /// Get a reference to the item with sequence number `seq`, if it's in
+    /// the window and has arrived.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, seq: u32) -> Option<&T> {
+        self.slots[self.index(seq)?].as_ref()
+    }
+
+    /// Get an [`Entry`] for the slot with sequence number `seq`, or [`None`]
+    /// if it's outside of the window.
+    #[inline]
+    #[must_use]
+    pub fn entry(&mut self, seq: u32) -> Option<Entry<'_, T>> {
+        let index = self.index(seq)?;
+        Some(examine(&mut self.slots[index]))
+    }
+
+    /// Insert the item with sequence number `seq`, replacing it if it had
+    /// already arrived, and return an [`Occupied`] reference to it. Returns
+    /// the item back if `seq` is outside of the window.
+    #[inline]
+    pub fn insert(&mut self, seq: u32, item: T) -> Result<Occupied<'_, T>, T> {
+        match self.index(seq) {
+            Some(index) => Ok(self.slots[index].emplace(item)),
+            None => Err(item),
+        }
+    }
+
+    /// Advance the window past the next expected sequence number, whether
+    /// or not it has arrived, returning its item, if any.
+    #[inline]
+    pub fn skip(&mut self) -> Option<T> {
+        let item = self.slots.get_mut(self.head)?.take();
+
+        self.head = (self.head + 1) % N;
+        self.base = self.base.wrapping_add(1);
+        item
+    }
+
+    /// Pop the item with the next expected sequence number, if it has
+    /// arrived, advancing the window.
+    #[inline]
+    pub fn pop_contiguous(&mut self) -> Option<T> {
+        match self.slots.get(self.head)?.is_some() {
+            true => self.skip(),
+            false => None,
+        }
+    }
+
+    /// Pop every item that's ready in order; that is, until the next
+    /// expected sequence number hasn't arrived yet.
+    #[inline]
+    pub fn drain_contiguous(&mut self) -> impl Iterator<Item = T> + '_ {
+        core::iter::from_fn(|| self.pop_contiguous())
+    }
+}
+
+impl<T, const N: usize> Default for SequenceSlots<T, N> {
+    /// Create a new, empty [`SequenceSlots`], expecting 0 as the first
+    /// sequence number.
+    #[inline]
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
@@ -0,0 +1,488 @@
+/*!
+Bulk operations on slices of optional values.
+*/
+
+use core::fmt::{self, Debug};
+
+use crate::{Occupied, OptionExt as _, Vacant};
+
+/// As with [`Occupied`] and [`Vacant`][crate::Vacant], the `slots` field is
+/// only directly accessible in here, to force the use of `unsafe{}` to
+/// construct the proof.
+mod internals {
+    /**
+    A reference to a slice of options that are statically guaranteed to all
+    be [`Some`], so that every element can be accessed unconditionally.
+    */
+    pub struct OccupiedSlice<'a, T> {
+        slots: &'a mut [Option<T>],
+    }
+
+    impl<'a, T> OccupiedSlice<'a, T> {
+        /**
+        Create a new [`OccupiedSlice`], referencing a slice of options that
+        are definitely all [`Some`].
+
+        # Safety
+
+        Every element of the `slots` parameter MUST be [`Some`].
+        */
+        #[inline(always)]
+        #[must_use]
+        #[allow(unsafe_code)]
+        pub const unsafe fn new_unchecked(slots: &'a mut [Option<T>]) -> Self {
+            Self { slots }
+        }
+
+        /// Safe equivalent of [`new_unchecked`][Self::new_unchecked], used
+        /// internally when `forbid-unsafe` is enabled.
+        #[cfg(feature = "forbid-unsafe")]
+        #[inline(always)]
+        #[must_use]
+        pub(crate) const fn new_trusted(slots: &'a mut [Option<T>]) -> Self {
+            Self { slots }
+        }
+
+        /**
+        Get a mutable reference to the underlying slice. This destroys
+        `self`, because we lose the guarantee that every slot is occupied.
+        */
+        #[inline(always)]
+        #[must_use]
+        pub const fn into_inner(self) -> &'a mut [Option<T>] {
+            self.slots
+        }
+
+        /// Get a shared reference to the underlying slice.
+        #[inline(always)]
+        #[must_use]
+        pub const fn as_slice(&self) -> &[Option<T>] {
+            self.slots
+        }
+
+        /// Get a mutable reference to the underlying slice, without
+        /// destroying `self`. Only used internally, since callers could use
+        /// it to break the invariant.
+        #[inline(always)]
+        pub(crate) const fn as_slice_mut(&mut self) -> &mut [Option<T>] {
+            self.slots
+        }
+    }
+
+    /**
+    A reference to a slice of options that are statically guaranteed to all
+    be [`None`].
+    */
+    pub struct VacantSlice<'a, T> {
+        slots: &'a mut [Option<T>],
+    }
+
+    impl<'a, T> VacantSlice<'a, T> {
+        /**
+        Create a new [`VacantSlice`], referencing a slice of options that are
+        definitely all [`None`].
+
+        # Safety
+
+        Every element of the `slots` parameter MUST be [`None`].
+        */
+        #[inline(always)]
+        #[must_use]
+        #[allow(unsafe_code)]
+        pub const unsafe fn new_unchecked(slots: &'a mut [Option<T>]) -> Self {
+            Self { slots }
+        }
+
+        /// Safe equivalent of [`new_unchecked`][Self::new_unchecked], used
+        /// internally when `forbid-unsafe` is enabled.
+        #[cfg(feature = "forbid-unsafe")]
+        #[inline(always)]
+        #[must_use]
+        pub(crate) const fn new_trusted(slots: &'a mut [Option<T>]) -> Self {
+            Self { slots }
+        }
+
+        /**
+        Get a mutable reference to the underlying slice. This destroys
+        `self`, because we lose the guarantee that every slot is vacant.
+        */
+        #[inline(always)]
+        #[must_use]
+        pub const fn into_inner(self) -> &'a mut [Option<T>] {
+            self.slots
+        }
+
+        /// Get a shared reference to the underlying slice.
+        #[inline(always)]
+        #[must_use]
+        pub const fn as_slice(&self) -> &[Option<T>] {
+            self.slots
+        }
+    }
+}
+
+pub use internals::{OccupiedSlice, VacantSlice};
+
+impl<'a, T> OccupiedSlice<'a, T> {
+    /**
+    Try to create a new [`OccupiedSlice`], referencing a slice of options
+    that are definitely all [`Some`]. Returns [`None`] if any of them are
+    [`None`].
+    */
+    #[inline]
+    #[must_use]
+    pub fn new(slots: &'a mut [Option<T>]) -> Option<Self> {
+        match slots.iter().all(Option::is_some) {
+            // Safety: we just checked that every slot is occupied
+            true => Some(new_unchecked!(OccupiedSlice, slots)),
+            false => None,
+        }
+    }
+
+    /// Get the number of slots in the slice.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Returns true if the slice has no slots.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+
+    /// Get a reference to the item at `index`, or [`None`] if it's out of
+    /// bounds.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice()
+            .get(index)
+            // Safety: every slot in an `OccupiedSlice` is occupied
+            .map(|slot| unwrap_unchecked!(slot.as_ref()))
+    }
+
+    /// Get a mutable reference to the item at `index`, or [`None`] if it's
+    /// out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_slice_mut()
+            .get_mut(index)
+            // Safety: every slot in an `OccupiedSlice` is occupied
+            .map(|slot| unwrap_unchecked!(slot.as_mut()))
+    }
+
+    /// Iterate over references to the items in the slice.
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.as_slice()
+            .iter()
+            // Safety: every slot in an `OccupiedSlice` is occupied
+            .map(|slot| unwrap_unchecked!(slot.as_ref()))
+    }
+
+    /// Iterate over mutable references to the items in the slice.
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> + ExactSizeIterator {
+        self.as_slice_mut()
+            .iter_mut()
+            // Safety: every slot in an `OccupiedSlice` is occupied
+            .map(|slot| unwrap_unchecked!(slot.as_mut()))
+    }
+
+    /**
+    Split this slice into an [`Occupied`] reference for every slot, with
+    the original lifetime.
+
+    # Example
+
+    ```
+    use occupied::SliceOptionExt as _;
+
+    let mut slots = [Some(1), None, Some(3)];
+    let mut taken = Vec::new();
+
+    for occupied in slots.fill_with_default().into_occupied() {
+        taken.push(occupied.take());
+    }
+
+    assert_eq!(taken, [1, 0, 3]);
+    assert_eq!(slots, [None, None, None]);
+    ```
+    */
+    #[inline]
+    pub fn into_occupied(
+        self,
+    ) -> impl DoubleEndedIterator<Item = Occupied<'a, T>> + ExactSizeIterator {
+        self.into_inner()
+            .iter_mut()
+            // Safety: every slot in an `OccupiedSlice` is occupied
+            .map(|slot| new_unchecked!(Occupied, slot))
+    }
+}
+
+impl<T: Debug> Debug for OccupiedSlice<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> VacantSlice<'a, T> {
+    /**
+    Try to create a new [`VacantSlice`], referencing a slice of options that
+    are definitely all [`None`]. Returns [`None`] if any of them are
+    [`Some`].
+    */
+    #[inline]
+    #[must_use]
+    pub fn new(slots: &'a mut [Option<T>]) -> Option<Self> {
+        match slots.iter().all(Option::is_none) {
+            // Safety: we just checked that every slot is vacant
+            true => Some(new_unchecked!(VacantSlice, slots)),
+            false => None,
+        }
+    }
+
+    /// Get the number of slots in the slice.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Returns true if the slice has no slots.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+
+    /// Fill every slot with an item produced by `fill`, and return an
+    /// [`OccupiedSlice`] proof for the whole slice.
+    #[inline]
+    pub fn fill_with(self, mut fill: impl FnMut() -> T) -> OccupiedSlice<'a, T> {
+        let slots = self.into_inner();
+        slots.iter_mut().for_each(|slot| *slot = Some(fill()));
+
+        // Safety: we just filled every slot
+        new_unchecked!(OccupiedSlice, slots)
+    }
+
+    /// Split this slice into a [`Vacant`] reference for every slot, with the
+    /// original lifetime.
+    #[inline]
+    pub fn into_vacant(self) -> impl DoubleEndedIterator<Item = Vacant<'a, T>> + ExactSizeIterator {
+        self.into_inner()
+            .iter_mut()
+            // Safety: every slot in a `VacantSlice` is vacant
+            .map(|slot| new_unchecked!(Vacant, slot))
+    }
+}
+
+impl<T> Debug for VacantSlice<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VacantSlice")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// A maximal run of slots with the same occupancy, from
+/// [`group_occupancy_runs`][SliceOptionExt::group_occupancy_runs].
+#[derive(Debug)]
+pub enum OccupancyRun<'a, T> {
+    /// A run of occupied slots
+    Occupied(OccupiedSlice<'a, T>),
+
+    /// A run of vacant slots
+    Vacant(VacantSlice<'a, T>),
+}
+
+impl<T> OccupancyRun<'_, T> {
+    /// Get the number of slots in the run.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        match self {
+            OccupancyRun::Occupied(run) => run.len(),
+            OccupancyRun::Vacant(run) => run.len(),
+        }
+    }
+
+    /// Returns true if the run has no slots. Runs from
+    /// [`group_occupancy_runs`][SliceOptionExt::group_occupancy_runs] are
+    /// never empty.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/**
+Additional methods for slices of options, for operating on all of their
+slots at once.
+*/
+pub trait SliceOptionExt<T> {
+    /**
+    Fill every vacant slot in this slice with an item produced by `fill`,
+    leaving occupied slots untouched, and return an [`OccupiedSlice`] proof
+    for the whole slice.
+    */
+    fn fill_vacant_with(&mut self, fill: impl FnMut() -> T) -> OccupiedSlice<'_, T>;
+
+    /**
+    Find the first vacant slot in this slice, returning its index and a
+    [`Vacant`] reference to it.
+
+    # Example
+
+    ```
+    use occupied::SliceOptionExt as _;
+
+    let mut slots = [Some("a"), None, None];
+
+    let (index, vacant) = slots.first_vacant().unwrap();
+    assert_eq!(index, 1);
+    vacant.insert("b");
+
+    assert_eq!(slots, [Some("a"), Some("b"), None]);
+    ```
+    */
+    #[must_use]
+    fn first_vacant(&mut self) -> Option<(usize, Vacant<'_, T>)>;
+
+    /**
+    Find the first vacant slot in this slice whose index satisfies
+    `predicate`, returning its index and a [`Vacant`] reference to it.
+
+    # Example
+
+    ```
+    use occupied::SliceOptionExt as _;
+
+    let mut slots = [None, Some(1), None, None];
+
+    // Slot 0 is reserved
+    let (index, vacant) = slots.find_vacant(|index| index != 0).unwrap();
+    assert_eq!(index, 2);
+    vacant.insert(2);
+    ```
+    */
+    #[must_use]
+    fn find_vacant(
+        &mut self,
+        predicate: impl FnMut(usize) -> bool,
+    ) -> Option<(usize, Vacant<'_, T>)>;
+
+    /**
+    Split this slice into maximal runs of occupied and vacant slots, in
+    order. Consecutive runs always alternate between occupied and vacant.
+
+    # Example
+
+    ```
+    use occupied::{OccupancyRun, SliceOptionExt as _};
+
+    let mut slots = [Some(1), Some(2), None, None, None, Some(3)];
+
+    let summary: Vec<String> = slots
+        .group_occupancy_runs()
+        .map(|run| match run {
+            OccupancyRun::Occupied(run) => format!("{} present", run.len()),
+            OccupancyRun::Vacant(run) => format!("{} gaps", run.len()),
+        })
+        .collect();
+
+    assert_eq!(summary, ["2 present", "3 gaps", "1 present"]);
+    ```
+    */
+    fn group_occupancy_runs<'a>(&'a mut self) -> impl Iterator<Item = OccupancyRun<'a, T>>
+    where
+        T: 'a;
+
+    /**
+    Fill every vacant slot in this slice with [`T::default()`][Default],
+    leaving occupied slots untouched, and return an [`OccupiedSlice`] proof
+    for the whole slice.
+
+    # Example
+
+    ```
+    use occupied::SliceOptionExt as _;
+
+    let mut slots = [Some(1), None, Some(3), None];
+    let mut all = slots.fill_with_default();
+
+    all.iter_mut().for_each(|item| *item += 10);
+    assert_eq!(all.iter().sum::<i32>(), 44);
+    assert_eq!(slots, [Some(11), Some(10), Some(13), Some(10)]);
+    ```
+    */
+    #[inline]
+    fn fill_with_default(&mut self) -> OccupiedSlice<'_, T>
+    where
+        T: Default,
+    {
+        self.fill_vacant_with(T::default)
+    }
+}
+
+impl<T> SliceOptionExt<T> for [Option<T>] {
+    #[inline]
+    fn fill_vacant_with(&mut self, mut fill: impl FnMut() -> T) -> OccupiedSlice<'_, T> {
+        self.iter_mut()
+            .filter_map(|slot| slot.peek_empty())
+            .for_each(|vacant| {
+                vacant.insert(fill());
+            });
+
+        // Safety: we just filled every vacant slot
+        new_unchecked!(OccupiedSlice, self)
+    }
+
+    #[inline]
+    fn first_vacant(&mut self) -> Option<(usize, Vacant<'_, T>)> {
+        self.find_vacant(|_| true)
+    }
+
+    #[inline]
+    fn find_vacant(
+        &mut self,
+        mut predicate: impl FnMut(usize) -> bool,
+    ) -> Option<(usize, Vacant<'_, T>)> {
+        self.iter_mut()
+            .enumerate()
+            .filter(|&(index, ref slot)| slot.is_none() && predicate(index))
+            .find_map(|(index, slot)| Some((index, slot.peek_empty()?)))
+    }
+
+    #[inline]
+    fn group_occupancy_runs<'a>(&'a mut self) -> impl Iterator<Item = OccupancyRun<'a, T>>
+    where
+        T: 'a,
+    {
+        let mut rest = self;
+
+        core::iter::from_fn(move || {
+            let occupied = rest.first()?.is_some();
+            let len = rest
+                .iter()
+                .position(|slot| slot.is_some() != occupied)
+                .unwrap_or(rest.len());
+
+            let (run, tail) = core::mem::take(&mut rest).split_at_mut(len);
+            rest = tail;
+
+            Some(match occupied {
+                // Safety: every slot in the run is occupied
+                true => OccupancyRun::Occupied(new_unchecked!(OccupiedSlice, run)),
+                // Safety: every slot in the run is vacant
+                false => OccupancyRun::Vacant(new_unchecked!(VacantSlice, run)),
+            })
+        })
+    }
+}
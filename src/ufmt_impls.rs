@@ -0,0 +1,128 @@
+/*!
+Implementations of [`ufmt`]'s formatting traits, as a lightweight alternative
+to `core::fmt`. These mirror the `core::fmt::Debug` implementations, and the
+proofs' `uDisplay` implementations forward to the payload.
+*/
+
+use ufmt::{Formatter, uDebug, uDisplay, uWrite};
+
+use crate::{
+    DirtySlot, Entry, Occupied, OccupiedSlice, PendingSlot, SlotEvent, UndoSlot, Vacant,
+    VersionedSlot, WatchedSlot,
+};
+
+impl<T: uDebug> uDebug for Occupied<'_, T> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.debug_tuple("Occupied")?.field(self.get())?.finish()
+    }
+}
+
+/**
+Formats the item in the option.
+
+# Example
+
+```
+use occupied::OptionExt as _;
+
+struct Buffer(String);
+
+impl ufmt::uWrite for Buffer {
+    type Error = core::convert::Infallible;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.0.push_str(s);
+        Ok(())
+    }
+}
+
+let mut option = Some(10);
+let occupied = option.peek_some().unwrap();
+
+let mut buffer = Buffer(String::new());
+ufmt::uwrite!(buffer, "{} / {:?}", occupied, occupied).unwrap();
+assert_eq!(buffer.0, "10 / Occupied(10)");
+```
+*/
+impl<T: uDisplay> uDisplay for Occupied<'_, T> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        self.get().fmt(f)
+    }
+}
+
+impl<T> uDebug for Vacant<'_, T> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.debug_struct("Vacant")?
+            .field("option", &None::<()>)?
+            .finish()
+    }
+}
+
+impl<T: uDebug> uDebug for Entry<'_, T> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        match self {
+            Entry::Occupied(occupied) => f.debug_tuple("Occupied")?.field(occupied)?.finish(),
+            Entry::Vacant(vacant) => f.debug_tuple("Vacant")?.field(vacant)?.finish(),
+        }
+    }
+}
+
+impl<T: uDebug> uDebug for OccupiedSlice<'_, T> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.debug_list()?.entries(self.iter())?.finish()
+    }
+}
+
+impl<T: uDebug> uDebug for DirtySlot<T> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.debug_struct("DirtySlot")?
+            .field("slot", &self.get())?
+            .field("dirty", &self.is_dirty())?
+            .finish()
+    }
+}
+
+impl<T: uDebug> uDebug for PendingSlot<T> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.debug_struct("PendingSlot")?
+            .field("current", &self.current())?
+            .field("pending", &self.pending())?
+            .finish()
+    }
+}
+
+impl<T: uDebug, const N: usize> uDebug for UndoSlot<T, N> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.debug_struct("UndoSlot")?
+            .field("current", &self.get())?
+            .field("history_len", &self.history_len())?
+            .finish()
+    }
+}
+
+impl<T: uDebug> uDebug for VersionedSlot<T> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.debug_struct("VersionedSlot")?
+            .field("slot", &self.get())?
+            .field("version", &self.version())?
+            .finish()
+    }
+}
+
+impl uDebug for SlotEvent {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.write_str(match self {
+            SlotEvent::Inserted => "Inserted",
+            SlotEvent::Modified => "Modified",
+            SlotEvent::Vacated => "Vacated",
+        })
+    }
+}
+
+impl<T: uDebug, F: FnMut(SlotEvent)> uDebug for WatchedSlot<T, F> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.debug_struct("WatchedSlot")?
+            .field("slot", &self.get())?
+            .finish()
+    }
+}
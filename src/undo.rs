@@ -0,0 +1,191 @@
+/*!
+A slot with a bounded history of its previous states.
+*/
+
+use core::fmt;
+
+use crate::{DynSlot, Occupied, OptionExt as _};
+
+/**
+An optional value that remembers up to `N` of its previous states, so that
+changes to it can be [undone][UndoSlot::undo]. Every [`insert`][Self::insert]
+or [`clear`][Self::clear] records the state it replaced (including vacancy);
+once `N` states are recorded, the oldest is forgotten.
+
+Changes made through an [`Occupied`] reference from
+[`peek_some`][Self::peek_some] aren't recorded.
+
+# Example
+
+```
+use occupied::UndoSlot;
+
+let mut slot: UndoSlot<&str, 2> = UndoSlot::new();
+
+slot.insert("a");
+slot.insert("b");
+slot.clear();
+assert_eq!(slot.get(), None);
+
+assert!(slot.undo());
+assert_eq!(slot.get(), Some(&"b"));
+
+assert!(slot.undo());
+assert_eq!(slot.get(), Some(&"a"));
+
+// Only 2 states are remembered
+assert!(!slot.undo());
+assert_eq!(slot.get(), Some(&"a"));
+```
+*/
+pub struct UndoSlot<T, const N: usize> {
+    current: Option<T>,
+
+    // A ring buffer of previous states. `head` is the index where the next
+    // state will be written, and `len` is the number of recorded states,
+    // which are the `len` entries before `head`.
+    history: [Option<T>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> UndoSlot<T, N> {
+    /// Create a new, vacant [`UndoSlot`] with no history.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            current: None,
+            history: [const { None }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Create a new [`UndoSlot`] containing `value`, with no history.
+    #[inline]
+    #[must_use]
+    pub const fn with_value(value: T) -> Self {
+        Self {
+            current: Some(value),
+            history: [const { None }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Get a reference to the current value, if any.
+    #[inline]
+    #[must_use]
+    pub const fn get(&self) -> Option<&T> {
+        self.current.as_ref()
+    }
+
+    /// Try to get an [`Occupied`] reference to the current value. Changes
+    /// made through this reference aren't recorded in the history.
+    #[inline]
+    #[must_use]
+    pub fn peek_some(&mut self) -> Option<Occupied<'_, T>> {
+        self.current.peek_some()
+    }
+
+    /// Get the number of previous states that are currently recorded, and
+    /// can be restored with [`undo`][Self::undo].
+    #[inline]
+    #[must_use]
+    pub const fn history_len(&self) -> usize {
+        self.len
+    }
+
+    /// Forget all of the recorded previous states.
+    #[inline]
+    pub fn clear_history(&mut self) {
+        self.history.iter_mut().for_each(|state| *state = None);
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Record a previous state, forgetting the oldest one if the history is
+    /// full.
+    fn record(&mut self, state: Option<T>) {
+        if N == 0 {
+            return;
+        }
+
+        self.history[self.head] = state;
+        self.head = (self.head + 1) % N;
+        self.len = Ord::min(self.len + 1, N);
+    }
+
+    /// Insert a value into this slot, recording the previous state, and
+    /// return an [`Occupied`] reference to the value.
+    #[inline]
+    pub fn insert(&mut self, value: T) -> Occupied<'_, T> {
+        let previous = self.current.take();
+        self.record(previous);
+        self.current.emplace(value)
+    }
+
+    /// Remove the current value, recording it as the previous state. Returns
+    /// false (and records nothing) if the slot was already vacant.
+    #[inline]
+    pub fn clear(&mut self) -> bool {
+        match self.current.take() {
+            Some(value) => {
+                self.record(Some(value));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Restore the most recently recorded state, dropping the current value,
+    /// if any. Returns false (and does nothing) if there's no recorded state.
+    #[inline]
+    pub fn undo(&mut self) -> bool {
+        if self.len == 0 {
+            return false;
+        }
+
+        self.head = (self.head + N - 1) % N;
+        self.len -= 1;
+        self.current = self.history[self.head].take();
+        true
+    }
+
+    /// Consume this slot, returning the current value, if any.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Option<T> {
+        self.current
+    }
+}
+
+impl<T, const N: usize> Default for UndoSlot<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for UndoSlot<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UndoSlot")
+            .field("current", &self.current)
+            .field("history_len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static, const N: usize> DynSlot for UndoSlot<T, N> {
+    #[inline]
+    fn is_occupied(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Remove the current value, recording it in the history.
+    #[inline]
+    fn clear(&mut self) {
+        UndoSlot::clear(self);
+    }
+}
@@ -0,0 +1,57 @@
+/*!
+Uniqueness-aware extraction for options of reference-counted pointers.
+*/
+
+use alloc::rc::Rc;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+
+use crate::Occupied;
+
+impl<'a, T> Occupied<'a, Rc<T>> {
+    /**
+    If this is the only strong reference to its value, take it out of the
+    option and unwrap it, leaving [`None`] in its place. Otherwise, leave the
+    option untouched and return `self`.
+
+    # Example
+
+    ```
+    use std::rc::Rc;
+    use occupied::OptionExt as _;
+
+    let mut slot = Some(Rc::new(String::from("hello")));
+    let shared = Rc::clone(slot.as_ref().unwrap());
+
+    let occupied = slot.peek_some().unwrap();
+    let occupied = occupied.take_if_unique().unwrap_err();
+    assert_eq!(**occupied.get(), "hello");
+
+    drop(shared);
+    assert_eq!(slot.peek_some().unwrap().take_if_unique().unwrap(), "hello");
+    assert_eq!(slot, None);
+    ```
+    */
+    #[inline]
+    pub fn take_if_unique(self) -> Result<T, Self> {
+        let (vacant, rc) = self.extract();
+
+        Rc::try_unwrap(rc).map_err(|rc| vacant.insert(rc))
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<'a, T> Occupied<'a, Arc<T>> {
+    /**
+    If this is the only strong reference to its value, take it out of the
+    option and unwrap it, leaving [`None`] in its place. Otherwise, leave the
+    option untouched and return `self`. The check and the extraction happen
+    atomically, as with [`Arc::try_unwrap`].
+    */
+    #[inline]
+    pub fn take_if_unique(self) -> Result<T, Self> {
+        let (vacant, arc) = self.extract();
+
+        Arc::try_unwrap(arc).map_err(|arc| vacant.insert(arc))
+    }
+}
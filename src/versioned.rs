@@ -0,0 +1,166 @@
+/*!
+A slot carrying a version number, which changes whenever the slot does.
+*/
+
+use crate::{DynSlot, Entry, Occupied, OptionExt as _, examine};
+
+/**
+An optional value with a version number, which is incremented whenever the
+slot is changed: when a value is inserted or removed, and whenever the value
+is mutably accessed. This allows pollers and caches to cheaply check whether
+the slot has changed since they last looked at it.
+
+# Example
+
+```
+use occupied::VersionedSlot;
+
+let mut slot = VersionedSlot::new();
+let mut seen = slot.version();
+
+slot.insert("hello");
+
+let (value, version) = slot.get_if_newer(seen).unwrap();
+assert_eq!(*value, "hello");
+seen = version;
+
+assert!(slot.get_if_newer(seen).is_none());
+
+slot.insert("world");
+assert_eq!(slot.get_if_newer(seen).map(|(value, _)| *value), Some("world"));
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct VersionedSlot<T> {
+    slot: Option<T>,
+    version: u64,
+}
+
+impl<T> VersionedSlot<T> {
+    /// Create a new, vacant [`VersionedSlot`], at version 0.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            slot: None,
+            version: 0,
+        }
+    }
+
+    /**
+    Create a new [`VersionedSlot`] containing `value`, at version 1. This is
+    the version it would have if it was created empty and then had `value`
+    inserted, so a poller starting from version 0 sees the initial value.
+
+    # Example
+
+    ```
+    use occupied::VersionedSlot;
+
+    let slot = VersionedSlot::with_value(10);
+    assert_eq!(slot.version(), 1);
+    assert_eq!(slot.get_if_newer(0), Some((&10, 1)));
+    ```
+    */
+    #[inline]
+    #[must_use]
+    pub const fn with_value(value: T) -> Self {
+        Self {
+            slot: Some(value),
+            version: 1,
+        }
+    }
+
+    /// Get the current version of this slot.
+    #[inline]
+    #[must_use]
+    pub const fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Get a reference to the value in this slot, if any.
+    #[inline]
+    #[must_use]
+    pub const fn get(&self) -> Option<&T> {
+        self.slot.as_ref()
+    }
+
+    /// Get a reference to the value in this slot, along with the current
+    /// version, but only if the version is newer than `last_seen` and the
+    /// slot is occupied.
+    #[inline]
+    #[must_use]
+    pub const fn get_if_newer(&self, last_seen: u64) -> Option<(&T, u64)> {
+        match self.slot {
+            Some(ref value) if self.version > last_seen => Some((value, self.version)),
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to the value in this slot, if any. This
+    /// increments the version if the slot is occupied.
+    #[inline]
+    #[must_use]
+    pub const fn get_mut(&mut self) -> Option<&mut T> {
+        if self.slot.is_some() {
+            self.version += 1;
+        }
+
+        self.slot.as_mut()
+    }
+
+    /// Insert a value into this slot, replacing any existing value, and
+    /// return an [`Occupied`] reference to it. This increments the version.
+    #[inline]
+    pub fn insert(&mut self, value: T) -> Occupied<'_, T> {
+        self.version += 1;
+        self.slot.emplace(value)
+    }
+
+    /// Remove the value from this slot, if any. This increments the version
+    /// if the slot was occupied.
+    #[inline]
+    pub const fn take(&mut self) -> Option<T> {
+        if self.slot.is_some() {
+            self.version += 1;
+        }
+
+        self.slot.take()
+    }
+
+    /// Call `body` with an [`Entry`] for this slot. Afterwards, the version
+    /// is incremented if the slot was occupied before or after (since `body`
+    /// may have changed it).
+    #[inline]
+    pub fn with_entry<R>(&mut self, body: impl FnOnce(Entry<'_, T>) -> R) -> R {
+        let before = self.slot.is_some();
+        let result = body(examine(&mut self.slot));
+
+        if before || self.slot.is_some() {
+            self.version += 1;
+        }
+
+        result
+    }
+
+    /// Consume this slot, returning the value, if any.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Option<T> {
+        self.slot
+    }
+}
+
+impl<T: 'static> DynSlot for VersionedSlot<T> {
+    #[inline]
+    fn is_occupied(&self) -> bool {
+        self.slot.is_some()
+    }
+
+    /// Remove the value from this slot, incrementing the version if it was
+    /// occupied.
+    #[inline]
+    fn clear(&mut self) {
+        self.take();
+    }
+}
@@ -0,0 +1,197 @@
+/*!
+A slot that reports changes to its occupancy or contents.
+*/
+
+use core::fmt;
+
+use crate::{DynSlot, Entry, examine};
+
+/// A change to a [`WatchedSlot`], reported to its callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotEvent {
+    /// A value was inserted into the previously vacant slot.
+    Inserted,
+
+    /// The value in the slot was replaced or mutably accessed.
+    Modified,
+
+    /// The value was removed from the slot, leaving it vacant.
+    Vacated,
+}
+
+/**
+An optional value that invokes a callback whenever it's changed: when a value
+is inserted or removed, and whenever the value is mutably accessed (since
+there's no way to know whether a mutable access actually changed anything).
+Shared access doesn't invoke the callback.
+
+Because any mutable access needs to be reported, this doesn't hand out
+[`Occupied`][crate::Occupied] references directly; instead, use
+[`with_entry`][WatchedSlot::with_entry] to work with an [`Entry`], and the
+change is reported afterwards.
+
+# Example
+
+```
+use std::cell::RefCell;
+use occupied::{SlotEvent, WatchedSlot};
+
+let events = RefCell::new(Vec::new());
+let mut slot = WatchedSlot::new(|event| events.borrow_mut().push(event));
+
+slot.replace(1);
+slot.replace(2);
+*slot.get_mut().unwrap() += 1;
+assert_eq!(slot.get(), Some(&3));
+slot.take();
+slot.with_entry(|entry| entry.or_insert(10).take());
+slot.with_entry(|entry| *entry.or_insert(10).get());
+
+assert_eq!(
+    *events.borrow(),
+    [
+        SlotEvent::Inserted,
+        SlotEvent::Modified,
+        SlotEvent::Modified,
+        SlotEvent::Vacated,
+        SlotEvent::Inserted,
+    ]
+);
+```
+*/
+pub struct WatchedSlot<T, F> {
+    slot: Option<T>,
+    on_change: F,
+}
+
+impl<T, F: FnMut(SlotEvent)> WatchedSlot<T, F> {
+    /// Create a new, vacant [`WatchedSlot`], which calls `on_change` whenever
+    /// it's changed.
+    #[inline]
+    #[must_use]
+    pub const fn new(on_change: F) -> Self {
+        Self {
+            slot: None,
+            on_change,
+        }
+    }
+
+    /// Create a new [`WatchedSlot`] containing `value`, which calls
+    /// `on_change` whenever it's changed. Creating the slot doesn't call
+    /// `on_change`.
+    #[inline]
+    #[must_use]
+    pub const fn with_value(value: T, on_change: F) -> Self {
+        Self {
+            slot: Some(value),
+            on_change,
+        }
+    }
+
+    /// Get a reference to the value in this slot, if any. This doesn't
+    /// report a change.
+    #[inline]
+    #[must_use]
+    pub const fn get(&self) -> Option<&T> {
+        self.slot.as_ref()
+    }
+
+    /// Returns true if this slot contains a value.
+    #[inline]
+    #[must_use]
+    pub const fn is_occupied(&self) -> bool {
+        self.slot.is_some()
+    }
+
+    /// Get a mutable reference to the value in this slot, if any. This
+    /// reports a [`SlotEvent::Modified`] if the slot is occupied.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if self.slot.is_some() {
+            (self.on_change)(SlotEvent::Modified);
+        }
+
+        self.slot.as_mut()
+    }
+
+    /// Insert a value into this slot, returning the previous value, if any.
+    /// This reports a [`SlotEvent::Inserted`] if the slot was vacant, or a
+    /// [`SlotEvent::Modified`] if it was occupied.
+    #[inline]
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        let old = self.slot.replace(value);
+
+        (self.on_change)(match old {
+            Some(_) => SlotEvent::Modified,
+            None => SlotEvent::Inserted,
+        });
+
+        old
+    }
+
+    /// Remove the value from this slot, if any. This reports a
+    /// [`SlotEvent::Vacated`] if the slot was occupied.
+    #[inline]
+    pub fn take(&mut self) -> Option<T> {
+        let old = self.slot.take();
+
+        if old.is_some() {
+            (self.on_change)(SlotEvent::Vacated);
+        }
+
+        old
+    }
+
+    /**
+    Call `body` with an [`Entry`] for this slot, then report the change, if
+    any: [`SlotEvent::Inserted`] or [`SlotEvent::Vacated`] if the occupancy
+    changed, or [`SlotEvent::Modified`] if the slot was occupied before and
+    after (since `body` had mutable access to the value).
+    */
+    #[inline]
+    pub fn with_entry<R>(&mut self, body: impl FnOnce(Entry<'_, T>) -> R) -> R {
+        let before = self.slot.is_some();
+        let result = body(examine(&mut self.slot));
+        let after = self.slot.is_some();
+
+        match (before, after) {
+            (false, true) => (self.on_change)(SlotEvent::Inserted),
+            (true, false) => (self.on_change)(SlotEvent::Vacated),
+            (true, true) => (self.on_change)(SlotEvent::Modified),
+            (false, false) => {}
+        }
+
+        result
+    }
+
+    /// Consume this slot, returning the value, if any. This doesn't report
+    /// a change.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Option<T> {
+        self.slot
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for WatchedSlot<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedSlot")
+            .field("slot", &self.slot)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static, F: FnMut(SlotEvent) + 'static> DynSlot for WatchedSlot<T, F> {
+    #[inline]
+    fn is_occupied(&self) -> bool {
+        self.slot.is_some()
+    }
+
+    /// Remove the value from this slot, reporting a [`SlotEvent::Vacated`]
+    /// if it was occupied.
+    #[inline]
+    fn clear(&mut self) {
+        self.take();
+    }
+}
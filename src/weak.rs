@@ -0,0 +1,271 @@
+/*!
+Slots holding weak references, which vacate themselves once the referenced
+value is gone.
+*/
+
+use alloc::rc::{self, Rc};
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::{self, Arc};
+
+use crate::{DynSlot, Entry, Occupied, OptionExt as _, examine};
+
+/**
+Weak references that can be upgraded to strong references, like
+[`rc::Weak`] and [`sync::Weak`].
+*/
+pub trait Upgrade {
+    /// The strong reference type, like [`Rc`] or [`Arc`].
+    type Strong;
+
+    /// Create a weak reference from a strong one.
+    #[must_use]
+    fn downgrade(strong: &Self::Strong) -> Self;
+
+    /// Try to upgrade this weak reference to a strong one, returning
+    /// [`None`] if the referenced value has been dropped.
+    #[must_use]
+    fn upgrade(&self) -> Option<Self::Strong>;
+}
+
+impl<T: ?Sized> Upgrade for rc::Weak<T> {
+    type Strong = Rc<T>;
+
+    #[inline]
+    fn downgrade(strong: &Rc<T>) -> Self {
+        Rc::downgrade(strong)
+    }
+
+    #[inline]
+    fn upgrade(&self) -> Option<Rc<T>> {
+        rc::Weak::upgrade(self)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T: ?Sized> Upgrade for sync::Weak<T> {
+    type Strong = Arc<T>;
+
+    #[inline]
+    fn downgrade(strong: &Arc<T>) -> Self {
+        Arc::downgrade(strong)
+    }
+
+    #[inline]
+    fn upgrade(&self) -> Option<Arc<T>> {
+        sync::Weak::upgrade(self)
+    }
+}
+
+/**
+An optional weak reference, which treats a dead reference (one that can no
+longer be [upgraded][Upgrade::upgrade]) as vacant, and lazily vacates itself
+when it notices that the reference has died.
+
+# Example
+
+```
+use std::rc::{Rc, Weak};
+use occupied::WeakSlot;
+
+let mut slot: WeakSlot<Weak<str>> = WeakSlot::new();
+assert!(slot.peek_upgrade().is_none());
+
+let value: Rc<str> = Rc::from("hello");
+slot.insert_strong(&value);
+assert_eq!(slot.peek_upgrade().as_deref(), Some("hello"));
+
+drop(value);
+assert!(slot.peek_upgrade().is_none());
+assert!(slot.entry().into_inner().is_none());
+```
+*/
+#[derive(Debug, Clone)]
+pub struct WeakSlot<W> {
+    slot: Option<W>,
+}
+
+impl<W> Default for WeakSlot<W> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W> WeakSlot<W> {
+    /// Create a new, vacant [`WeakSlot`].
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { slot: None }
+    }
+
+    /// Insert a weak reference into this slot, replacing any existing one,
+    /// and return an [`Occupied`] reference to it.
+    #[inline]
+    pub fn insert(&mut self, weak: W) -> Occupied<'_, W> {
+        self.slot.emplace(weak)
+    }
+
+    /// Remove the weak reference from this slot, whether or not it's still
+    /// alive.
+    #[inline]
+    pub const fn take(&mut self) -> Option<W> {
+        self.slot.take()
+    }
+
+    /// Consume this slot, returning the weak reference, whether or not it's
+    /// still alive.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Option<W> {
+        self.slot
+    }
+}
+
+impl<W: Upgrade> WeakSlot<W> {
+    /// Create a new [`WeakSlot`] referencing the same value as `strong`.
+    #[inline]
+    #[must_use]
+    pub fn from_strong(strong: &W::Strong) -> Self {
+        Self {
+            slot: Some(W::downgrade(strong)),
+        }
+    }
+
+    /// Insert a weak reference to the value in `strong` into this slot,
+    /// replacing any existing one, and return an [`Occupied`] reference to
+    /// it.
+    #[inline]
+    pub fn insert_strong(&mut self, strong: &W::Strong) -> Occupied<'_, W> {
+        self.insert(W::downgrade(strong))
+    }
+
+    /**
+    Try to upgrade the weak reference in this slot. Returns [`None`] if the
+    slot is vacant, or if the referenced value has been dropped, in which
+    case the slot is vacated.
+    */
+    #[inline]
+    #[must_use]
+    pub fn peek_upgrade(&mut self) -> Option<W::Strong> {
+        let occupied = self.slot.peek_some()?;
+
+        match occupied.get().upgrade() {
+            Some(strong) => Some(strong),
+            None => {
+                occupied.take();
+                None
+            }
+        }
+    }
+
+    /**
+    Get an [`Entry`] for this slot. If the weak reference is dead, the slot
+    is vacated first, so an [`Entry::Occupied`] means that the reference was
+    alive when this was called.
+    */
+    #[inline]
+    #[must_use]
+    pub fn entry(&mut self) -> Entry<'_, W> {
+        self.prune();
+        examine(&mut self.slot)
+    }
+
+    /// Returns true if this slot holds a live weak reference. If the weak
+    /// reference is dead, the slot is vacated.
+    #[inline]
+    #[must_use]
+    pub fn is_alive(&mut self) -> bool {
+        self.prune();
+        self.slot.is_some()
+    }
+
+    /// Vacate this slot if its weak reference is dead
+    #[inline]
+    fn prune(&mut self) {
+        if let Some(occupied) = self.slot.peek_some()
+            && occupied.get().upgrade().is_none()
+        {
+            occupied.take();
+        }
+    }
+}
+
+impl<W: Upgrade + 'static> DynSlot for WeakSlot<W> {
+    /// A [`WeakSlot`] is occupied if it holds a live weak reference.
+    #[inline]
+    fn is_occupied(&self) -> bool {
+        self.slot
+            .as_ref()
+            .is_some_and(|weak| weak.upgrade().is_some())
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.slot = None;
+    }
+}
+
+/**
+Vacate every slot in `slots` whose weak reference is dead, returning the
+number of slots that were vacated. See [`prune_dead_with`] to also get the
+upgraded strong references for the survivors.
+
+# Example
+
+```
+use std::rc::Rc;
+
+let a = Rc::new(1);
+let b = Rc::new(2);
+let mut observers = [Some(Rc::downgrade(&a)), None, Some(Rc::downgrade(&b))];
+
+drop(a);
+assert_eq!(occupied::prune_dead(&mut observers), 1);
+assert!(observers[0].is_none());
+assert!(observers[2].is_some());
+```
+*/
+#[inline]
+pub fn prune_dead<W: Upgrade>(slots: &mut [Option<W>]) -> usize {
+    prune_dead_with(slots, |_strong| {})
+}
+
+/**
+Vacate every slot in `slots` whose weak reference is dead, passing the
+upgraded strong reference of every surviving slot to `survivor`. Returns the
+number of slots that were vacated.
+
+# Example
+
+```
+use std::rc::Rc;
+
+let a = Rc::new(1);
+let b = Rc::new(2);
+let mut observers = [Some(Rc::downgrade(&a)), Some(Rc::downgrade(&b))];
+
+drop(b);
+let mut alive = Vec::new();
+assert_eq!(occupied::prune_dead_with(&mut observers, |rc| alive.push(rc)), 1);
+assert_eq!(alive, [Rc::new(1)]);
+```
+*/
+pub fn prune_dead_with<W: Upgrade>(
+    slots: &mut [Option<W>],
+    mut survivor: impl FnMut(W::Strong),
+) -> usize {
+    let mut pruned = 0;
+
+    for occupied in slots.iter_mut().filter_map(|slot| slot.peek_some()) {
+        match occupied.get().upgrade() {
+            Some(strong) => survivor(strong),
+            None => {
+                occupied.take();
+                pruned += 1;
+            }
+        }
+    }
+
+    pruned
+}